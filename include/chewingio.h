@@ -150,6 +150,9 @@ CHEWING_API int chewing_handle_CtrlNum( ChewingContext *ctx, int key );
 
 /**
  * @brief Handle the input key stroke: Shift + Space
+ *
+ * Toggle between full shape and half shape mode.
+ *
  * @param ctx Chewing IM context
  */
 CHEWING_API int chewing_handle_ShiftSpace( ChewingContext *ctx );
@@ -169,6 +172,111 @@ CHEWING_API int chewing_handle_Numlock( ChewingContext *ctx, int key);
 /*@}*/
 
 
+/*! \name Editing buffer operations
+ */
+
+/*@{*/
+/**
+ * @brief Commit the content of the pre-edit buffer
+ * @param ctx Chewing IM context
+ * @retval 0 on success
+ * @retval -1 if the pre-edit buffer is empty or candidates are shown
+ */
+CHEWING_API int chewing_commit_preedit_buf( ChewingContext *ctx );
+
+/**
+ * @brief Clear the pre-edit buffer without committing it
+ * @param ctx Chewing IM context
+ * @retval 0 on success
+ * @retval -1 if candidates are shown
+ */
+CHEWING_API int chewing_clean_preedit_buf( ChewingContext *ctx );
+
+/**
+ * @brief Clear the phonetic symbols in the bopomofo buffer
+ * @param ctx Chewing IM context
+ * @retval 0 on success
+ */
+CHEWING_API int chewing_clean_bopomofo_buf( ChewingContext *ctx );
+/*@}*/
+
+
+/*! \name Candidate window operations
+ */
+
+/*@{*/
+/**
+ * @brief Open the candidate window at the cursor
+ * @param ctx Chewing IM context
+ * @retval 0 on success, or if the window is already open
+ * @retval -1 if there is no candidate at the cursor
+ */
+CHEWING_API int chewing_cand_open( ChewingContext *ctx );
+
+/**
+ * @brief Close the candidate window
+ * @param ctx Chewing IM context
+ * @retval 0 on success
+ */
+CHEWING_API int chewing_cand_close( ChewingContext *ctx );
+
+/**
+ * @brief Choose a candidate in the candidate window
+ * @param ctx Chewing IM context
+ * @param index Index of the candidate in the whole list, starting from 0
+ * @retval 0 on success
+ * @retval -1 if the window is not open or the index is out of range
+ */
+CHEWING_API int chewing_cand_choose_by_index( ChewingContext *ctx, int index );
+
+/**
+ * @brief Show the candidates of the first phrase in the listing order
+ * @param ctx Chewing IM context
+ * @retval 0 on success
+ * @retval -1 if the window is not open or shows symbols
+ */
+CHEWING_API int chewing_cand_list_first( ChewingContext *ctx );
+
+/**
+ * @brief Show the candidates of the last phrase in the listing order
+ * @param ctx Chewing IM context
+ * @retval 0 on success
+ * @retval -1 if the window is not open or shows symbols
+ */
+CHEWING_API int chewing_cand_list_last( ChewingContext *ctx );
+
+/**
+ * @brief Check if there is a next phrase in the listing order
+ * @param ctx Chewing IM context
+ * @return 1 if chewing_cand_list_next() can be called, 0 otherwise
+ */
+CHEWING_API int chewing_cand_list_has_next( ChewingContext *ctx );
+
+/**
+ * @brief Check if there is a previous phrase in the listing order
+ * @param ctx Chewing IM context
+ * @return 1 if chewing_cand_list_prev() can be called, 0 otherwise
+ */
+CHEWING_API int chewing_cand_list_has_prev( ChewingContext *ctx );
+
+/**
+ * @brief Show the candidates of the next phrase in the listing order
+ * @param ctx Chewing IM context
+ * @retval 0 on success
+ * @retval -1 if there is no next phrase
+ */
+CHEWING_API int chewing_cand_list_next( ChewingContext *ctx );
+
+/**
+ * @brief Show the candidates of the previous phrase in the listing order
+ * @param ctx Chewing IM context
+ * @retval 0 on success
+ * @retval -1 if there is no previous phrase
+ */
+CHEWING_API int chewing_cand_list_prev( ChewingContext *ctx );
+/*@}*/
+
+
 /*! \name Chewing IM Instance Management
  */
 
@@ -179,6 +287,25 @@ CHEWING_API int chewing_handle_Numlock( ChewingContext *ctx, int key);
  */
 CHEWING_API ChewingContext *chewing_new();
 
+/**
+ * @brief Create new handle of the instance for Chewing IM with given paths
+ * @see chewing_new()
+ * @see chewing_delete()
+ *
+ * @param syspath search path of the system dictionary, or NULL to use
+ *        the default search path
+ * @param userpath directory of the user phrase database, or NULL to use
+ *        the default directory. The creation fails if the directory is
+ *        not writable.
+ * @param logger callback receiving log messages, or NULL to disable logging
+ * @param loggerdata first argument passed to logger
+ */
+CHEWING_API ChewingContext *chewing_new2(
+	const char *syspath,
+	const char *userpath,
+	void (*logger)( void *data, int level, const char *fmt, ... ),
+	void *loggerdata );
+
 /**
  * @brief Release the handle and internal memory by given Chewing instance
  * @see chewing_new()
@@ -187,6 +314,36 @@ CHEWING_API ChewingContext *chewing_new();
  */
 CHEWING_API void chewing_delete( ChewingContext *ctx );
 
+/**
+ * @brief Replace the logger of given Chewing instance
+ * @see chewing_new2()
+ *
+ * @param ctx Chewing IM context
+ * @param logger callback receiving log messages, or NULL to disable logging
+ * @param data first argument passed to logger
+ */
+CHEWING_API void chewing_set_logger( ChewingContext *ctx,
+	void (*logger)( void *data, int level, const char *fmt, ... ),
+	void *data );
+
+/**
+ * @brief Get the directory where the system dictionary is loaded from
+ *
+ * @param ctx Chewing IM context
+ * @return The returned string is owned by ctx and valid until
+ *         chewing_delete() is called.
+ */
+CHEWING_API const char *chewing_dict_path_Get( ChewingContext *ctx );
+
+/**
+ * @brief Get the file name of the user phrase database
+ *
+ * @param ctx Chewing IM context
+ * @return The returned string is owned by ctx and valid until
+ *         chewing_delete() is called.
+ */
+CHEWING_API const char *chewing_userphrase_path_Get( ChewingContext *ctx );
+
 /**
  * @brief Release memory allocated used by given pointer used in APIs
  */
@@ -267,7 +424,7 @@ CHEWING_API char* chewing_get_KBString( ChewingContext *ctx );
  *
  * @param str[] name of kbtype eg. "KB_HSU"
  */
-CHEWING_API int chewing_KBStr2Num( char str[] );
+CHEWING_API int chewing_KBStr2Num( const char str[] );
 /*@}*/
 
 
@@ -423,6 +580,256 @@ CHEWING_API int chewing_get_spaceAsSelection( ChewingContext *ctx );
 /*@}*/
 
 
+/*! \name Behavior whether if numpad keys are regarded as selection keys
+ */
+
+/*@{*/
+/**
+ * @brief Set the behavior if numpad keys are regarded as selection keys
+ * when the candidate window is open
+ *
+ * @param ctx
+ * @param mode 1 for selection keys (default), 0 for numeric input
+ */
+CHEWING_API void chewing_set_numpadAsSelection( ChewingContext *ctx, int mode );
+
+/**
+ * @brief Get the behavior if numpad keys are regarded as selection keys
+ *
+ * @param ctx
+ */
+CHEWING_API int chewing_get_numpadAsSelection( ChewingContext *ctx );
+/*@}*/
+
+
+/*! \name Ranking recently used user phrases in the candidate window
+ */
+
+/*@{*/
+/**
+ * @brief Set whether recently used user phrases are listed before other
+ * candidates
+ *
+ * @param ctx
+ * @param mode 1 to list recent user phrases first, 0 (default) otherwise
+ */
+CHEWING_API void chewing_set_recentPhraseFirst( ChewingContext *ctx, int mode );
+
+/**
+ * @brief Get whether recently used user phrases are listed before other
+ * candidates
+ *
+ * @param ctx
+ */
+CHEWING_API int chewing_get_recentPhraseFirst( ChewingContext *ctx );
+/*@}*/
+
+
+/*! \name Behavior whether if opening brackets are paired automatically
+ */
+
+/*@{*/
+/**
+ * @brief Set whether typing an opening bracket such as 「 also inserts the
+ * closing one after the cursor
+ *
+ * @param ctx
+ * @param mode 1 to pair brackets, 0 (default) otherwise
+ */
+CHEWING_API void chewing_set_autoPairSymbol( ChewingContext *ctx, int mode );
+
+/**
+ * @brief Get whether opening brackets are paired automatically
+ *
+ * @param ctx
+ */
+CHEWING_API int chewing_get_autoPairSymbol( ChewingContext *ctx );
+/*@}*/
+
+
+/*! \name Learning phrases automatically
+ */
+
+/*@{*/
+/**
+ * @brief Set whether committed phrases are learned automatically
+ *
+ * @param ctx
+ * @param mode AUTOLEARN_ENABLED (default) or AUTOLEARN_DISABLED
+ */
+CHEWING_API void chewing_set_autoLearn( ChewingContext *ctx, int mode );
+
+/**
+ * @brief Get whether committed phrases are learned automatically
+ *
+ * @param ctx
+ * @return AUTOLEARN_ENABLED or AUTOLEARN_DISABLED
+ */
+CHEWING_API int chewing_get_autoLearn( ChewingContext *ctx );
+/*@}*/
+
+
+/*! \name Phrases which are never learned automatically
+ */
+
+/*@{*/
+/**
+ * @brief Add or remove a phrase in the auto-learn suppression list
+ *
+ * Phrases in the list are not added to the user phrase database when the
+ * pre-edit buffer is committed. Selecting them manually still works.
+ *
+ * @param ctx
+ * @param phrase phrase in UTF-8
+ * @param suppress 1 to add the phrase, 0 to remove it
+ *
+ * @return 0 on success, -1 on failure
+ */
+CHEWING_API int chewing_set_autoLearnSuppression( ChewingContext *ctx, const char *phrase, int suppress );
+
+/**
+ * @brief Check if a phrase is in the auto-learn suppression list
+ *
+ * @param ctx
+ * @param phrase phrase in UTF-8
+ *
+ * @return 1 if the phrase is suppressed, 0 otherwise
+ */
+CHEWING_API int chewing_get_autoLearnSuppression( ChewingContext *ctx, const char *phrase );
+/*@}*/
+
+
+/*! \name User phrase database
+ *
+ * Bopomofo strings are phonetic symbols of each character separated by
+ * space, eg. "ㄘㄜˋ ㄕˋ" for "測試".
+ */
+
+/*@{*/
+/**
+ * @brief Start enumerating user phrases
+ *
+ * Adding phrases during the enumeration might not be reflected.
+ *
+ * @param ctx
+ * @return 0 on success
+ */
+CHEWING_API int chewing_userphrase_enumerate( ChewingContext *ctx );
+
+/**
+ * @brief Check if there are more user phrases to enumerate
+ *
+ * @param ctx
+ * @param[out] phrase_len buffer size required for the phrase, or NULL
+ * @param[out] bopomofo_len buffer size required for the bopomofo, or NULL
+ * @return 1 if there is next user phrase, 0 otherwise
+ */
+CHEWING_API int chewing_userphrase_has_next(
+	ChewingContext *ctx,
+	unsigned int *phrase_len,
+	unsigned int *bopomofo_len );
+
+/**
+ * @brief Get the current user phrase and move to the next one
+ *
+ * @param ctx
+ * @param[out] phrase_buf buffer of the phrase
+ * @param phrase_len size of phrase_buf
+ * @param[out] bopomofo_buf buffer of the bopomofo
+ * @param bopomofo_len size of bopomofo_buf
+ * @return 0 on success, -1 on failure
+ */
+CHEWING_API int chewing_userphrase_get(
+	ChewingContext *ctx,
+	char *phrase_buf, unsigned int phrase_len,
+	char *bopomofo_buf, unsigned int bopomofo_len );
+
+/**
+ * @brief Add a phrase to the user phrase database
+ *
+ * @param ctx
+ * @param phrase_buf phrase in UTF-8
+ * @param bopomofo_buf bopomofo of the phrase
+ * @return number of phrases added, 0 if the phrase exists or is invalid
+ */
+CHEWING_API int chewing_userphrase_add(
+	ChewingContext *ctx,
+	const char *phrase_buf,
+	const char *bopomofo_buf );
+
+/**
+ * @brief Remove a phrase from the user phrase database
+ *
+ * @param ctx
+ * @param phrase_buf phrase in UTF-8
+ * @param bopomofo_buf bopomofo of the phrase
+ * @return number of phrases removed
+ */
+CHEWING_API int chewing_userphrase_remove(
+	ChewingContext *ctx,
+	const char *phrase_buf,
+	const char *bopomofo_buf );
+
+/**
+ * @brief Check if a phrase is in the user phrase database
+ *
+ * @param ctx
+ * @param phrase_buf phrase in UTF-8
+ * @param bopomofo_buf bopomofo of the phrase
+ * @return 1 if the phrase exists, 0 otherwise
+ */
+CHEWING_API int chewing_userphrase_lookup(
+	ChewingContext *ctx,
+	const char *phrase_buf,
+	const char *bopomofo_buf );
+
+/**
+ * @brief Set the maximum number of user phrases
+ *
+ * When the limit is reached, the least recently used phrase is removed to
+ * make room for a new one. Phrases over the limit are removed immediately.
+ *
+ * @param ctx
+ * @param n maximum number of user phrases, 0 for unlimited
+ */
+CHEWING_API void chewing_set_maxUserPhrase( ChewingContext *ctx, int n );
+
+/**
+ * @brief Get the maximum number of user phrases
+ *
+ * @param ctx
+ * @return maximum number of user phrases, 0 for unlimited
+ */
+CHEWING_API int chewing_get_maxUserPhrase( ChewingContext *ctx );
+/*@}*/
+
+
+/*! \name Near-miss bopomofo correction
+ */
+
+/*@{*/
+/**
+ * @brief Set whether syllables without words are corrected
+ *
+ * When enabled, a syllable without any word is replaced by a near-miss one,
+ * which differs in the tone or in one key adjacent on the keyboard. The
+ * correction is shown in the auxiliary message.
+ *
+ * @param ctx
+ * @param mode 1 to enable, 0 to disable
+ */
+CHEWING_API void chewing_set_phoneCorrection( ChewingContext *ctx, int mode );
+
+/**
+ * @brief Get whether syllables without words are corrected
+ *
+ * @param ctx
+ * @return 1 if enabled, 0 otherwise
+ */
+CHEWING_API int chewing_get_phoneCorrection( ChewingContext *ctx );
+/*@}*/
+
+
 /*! \name Behavior whether if Escape should clean all buffer
  */
 
@@ -451,8 +858,13 @@ CHEWING_API int chewing_get_escCleanAllBuf( ChewingContext *ctx );
 /**
  * @brief Set the type of selection keys in Hsu's keyboard mapping
  *
+ * With KB_HSU or KB_DVORAK_HSU, the selection keys are replaced with the
+ * preset of the type, which can be read back with chewing_get_selKey(). The
+ * preset is also applied when chewing_set_KBType() switches to one of these
+ * layouts. Other layouts keep their selection keys.
+ *
  * @param ctx
- * @param mode
+ * @param mode HSU_SELKEY_TYPE1 or HSU_SELKEY_TYPE2
  */
 CHEWING_API void chewing_set_hsuSelKeyType( ChewingContext *ctx, int mode );
 
@@ -528,6 +940,27 @@ CHEWING_API int chewing_get_phraseChoiceRearward( ChewingContext *ctx );
 /*@}*/
 
 
+/*! \name Order of phrase lengths in phrase choice
+ */
+
+/*@{*/
+/**
+ * @brief Set whether phrase choice lists the shortest phrase first
+ *
+ * @param ctx
+ * @param mode 1 for shortest first, 0 (default) for longest first
+ */
+CHEWING_API void chewing_set_shortestPhraseFirst( ChewingContext *ctx, int mode );
+
+/**
+ * @brief Get whether phrase choice lists the shortest phrase first
+ *
+ * @param ctx
+ */
+CHEWING_API int chewing_get_shortestPhraseFirst( ChewingContext *ctx );
+/*@}*/
+
+
 /*! \name Phonetic sequence in Chewing internal state machine
  */
 
@@ -89,6 +89,25 @@ typedef struct {
  */
 typedef struct _ChewingContext ChewingContext;
 
+/** @brief keyboard layouts used by chewing_set_KBType()
+ *
+ * The names returned by chewing_kbtype_String() are the same as these
+ * identifiers, e.g. "KB_HSU" for KB_HSU.
+ */
+enum {
+	KB_DEFAULT,
+	KB_HSU,
+	KB_IBM,
+	KB_GIN_YIEH,
+	KB_ET,
+	KB_ET26,
+	KB_DVORAK,
+	KB_DVORAK_HSU,
+	KB_DACHEN_CP26,
+	KB_HANYU_PINYIN,
+	KB_TYPE_NUM
+};
+
 /** @brief use "asdfjkl789" as selection key
  */
 #define HSU_SELKEY_TYPE1 1
@@ -97,4 +116,21 @@ typedef struct _ChewingContext ChewingContext;
  */
 #define HSU_SELKEY_TYPE2 2
 
+/** @brief learn phrases automatically when they are committed
+ */
+#define AUTOLEARN_ENABLED 0
+
+/** @brief do not learn phrases automatically
+ */
+#define AUTOLEARN_DISABLED 1
+
+/** @brief log levels passed to the logger of chewing_new2() and
+ *         chewing_set_logger()
+ */
+#define CHEWING_LOG_VERBOSE 1
+#define CHEWING_LOG_DEBUG   2
+#define CHEWING_LOG_INFO    3
+#define CHEWING_LOG_WARN    4
+#define CHEWING_LOG_ERROR   5
+
 #endif
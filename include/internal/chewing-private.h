@@ -31,6 +31,8 @@
 #define MAX_PHRASE_LEN 10
 #define MAX_PHONE_SEQ_LEN 50
 #define MAX_INTERVAL ( ( MAX_PHONE_SEQ_LEN + 1 ) * MAX_PHONE_SEQ_LEN / 2 )
+#define MIN_CHI_SYMBOL_LEN 0
+#define MAX_CHI_SYMBOL_LEN ( MAX_PHONE_SEQ_LEN - MAX_PHRASE_LEN )
 #define MAX_CHOICE (567)
 #define MAX_CHOICE_BUF (50)                   /* max length of the choise buffer */
 #define N_HASH_BIT (14)
@@ -109,6 +111,8 @@ typedef struct {
 	int nTotalChoice;
 	int oldChiSymbolCursor;
 	int isSymbol;
+	/** @brief the next selection key removes the user phrase. */
+	int bRemoveUserPhrase;
 } ChoiceInfo;
 
 /** @brief entry of symbol table */
@@ -163,12 +167,26 @@ typedef struct {
 #else
 	FILE *dictfile;
 #endif
-
+	/* directory where the dictionary is loaded from */
+	char dict_path[ PATH_MAX ];
 
 	int chewing_lifetime;
 
 	char hashfilename[ 200 ];
 	struct tag_HASH_ITEM *hashtable[ HASH_TABLE_SIZE ];
+	/* number of items in hashtable */
+	int n_hash_item;
+	/* next item of chewing_userphrase_enumerate() */
+	struct tag_HASH_ITEM *userphrase_enum;
+	/* last item returned by UserGetPhraseFirst() and UserGetPhraseNext() */
+	struct tag_HASH_ITEM *userphrase_last;
+	/* cleared records in the hash file which can be reused */
+	int n_hash_free_slot;
+	int *hash_free_slot;
+
+	/* phrases which are never learned automatically */
+	unsigned int n_suppressed_phrase;
+	char **suppressed_phrase;
 
 	unsigned int n_symbol_entry;
 	SymbolEntry ** symbol_table;
@@ -184,12 +202,29 @@ typedef struct {
 
 struct tag_HASH_ITEM;
 
+/**
+ * @brief Configurations which are not exported by ChewingConfigData.
+ *
+ * ChewingConfigData is part of the public API, so new configurations are kept
+ * here in order not to break the binary compatibility.
+ */
+typedef struct {
+	int bNumpadAsSelection;
+	int autoLearn;
+	int bRecentPhraseFirst;
+	int bAutoPairSymbol;
+	int bShortestPhraseFirst;
+	int maxUserPhrase;
+	int bPhoneCorrection;
+} ChewingExtConfigData;
+
 typedef struct {
 	AvailInfo availInfo;
 	ChoiceInfo choiceInfo;
 	PhrasingOutput phrOut;
 	ZuinData zuinData;
 	ChewingConfigData config;
+	ChewingExtConfigData ext_config;
     /** @brief current input buffer, content==0 means Chinese code */
 	wch_t chiSymbolBuf[ MAX_PHONE_SEQ_LEN ];
 	int chiSymbolCursor;
@@ -216,6 +251,9 @@ typedef struct {
 	char symbolKeyBuf[ MAX_PHONE_SEQ_LEN ];
 
 	ChewingStaticData static_data;
+
+	void (*logger)( void *data, int level, const char *fmt, ... );
+	void *loggerData;
 } ChewingData;
 
 typedef struct {
@@ -260,6 +298,11 @@ struct _ChewingContext {
 	int cand_no;
 	int it_no;
 	int kb_no;
+	char bopomofo_buf[ ( 1 + ZUIN_SIZE ) * ( MAX_UTF8_SIZE + 1 ) ];
+	char commit_buf[ MAX_PHONE_SEQ_LEN * MAX_UTF8_SIZE + 1 ];
+	char preedit_buf[ MAX_PHONE_SEQ_LEN * MAX_UTF8_SIZE + 1 ];
+	char cand_buf[ MAX_PHRASE_LEN * MAX_UTF8_SIZE + 1 ];
+	char aux_buf[ MAX_PHONE_SEQ_LEN * MAX_UTF8_SIZE + 1 ];
 };
 /**
  * @struct ChewingContext
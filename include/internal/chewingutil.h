@@ -24,10 +24,12 @@
 
 void AutoLearnPhrase( ChewingData *pgdata );
 void SetUpdatePhraseMsg( ChewingData *pgdata, char *addWordSeq, int len, int state );
+void SetCorrectPhoneMsg( ChewingData *pgdata, uint16_t phone );
 int NoSymbolBetween( ChewingData *pgdata, int begin, int end );
 int ChewingIsEntering( ChewingData *pgdata );
 void CleanAllBuf( ChewingData * );
 int SpecialSymbolInput( int key, ChewingData *pgdata );
+int SpecialSymbolPair( int key, ChewingData *pgdata );
 int FullShapeSymbolInput( int key, ChewingData *pgdata );
 int EasySymbolInput( int key, ChewingData *pgdata );
 int SymbolInput( int key, ChewingData *pgdata );
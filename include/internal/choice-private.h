@@ -12,9 +12,11 @@
 #define _CHEWING_CHOICE_PRIVATE_H
 
 int ChoiceFirstAvail( ChewingData * );
-int ChoiceNextAvail( ChewingData * );
 int ChoicePrevAvail( ChewingContext * );
+int ChoiceSetAvail( ChewingData *, int avail );
+int ChoiceAvailOrder( ChewingData *, int n );
 int ChoiceSelect( ChewingData *, int selectNo );
 int ChoiceEndChoice( ChewingData * );
+int ChoiceRemoveUserPhrase( ChewingData *, int selectNo );
 
 #endif
@@ -34,9 +34,12 @@ HASH_ITEM *HashFindPhone( const uint16_t phoneSeq[] );
 HASH_ITEM *HashFindEntry( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] );
 HASH_ITEM *HashInsert( ChewingData *pgdata, UserPhraseData *pData );
 HASH_ITEM *HashFindPhonePhrase( ChewingData *pgdata, const uint16_t phoneSeq[], HASH_ITEM *pHashLast );
+HASH_ITEM *HashNext( ChewingData *pgdata, HASH_ITEM *pItem );
 void HashModify( ChewingData *pgdata, HASH_ITEM *pItem );
+int HashRemove( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] );
 int AlcUserPhraseSeq( UserPhraseData *pData, int phonelen, int wordlen );
-int InitHash( ChewingData *ctx );
+int isValidChineseString( const char *str );
+int InitHash( ChewingData *ctx, const char *path );
 void TerminateHash( ChewingData *pgdata );
 void FreeHashTable( void );
 
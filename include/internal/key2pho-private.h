@@ -21,8 +21,11 @@
 #  include <stdint.h>
 #endif
 
+#include <stddef.h>
+
 uint16_t UintFromPhone( const char *phone );
 uint16_t UintFromPhoneInx( const int ph_inx[] );
+int PhoneFromUint( char *phone, size_t phone_len, uint16_t phone_num );
 int PhoneFromKey( char *pho, const char *inputkey, int kbtype, int searchTimes );
 int PhoneInxFromKey( int key, int type, int kbtype, int searchTimes );
 
@@ -18,11 +18,14 @@
 #define MEDIUM_INCREASE_FREQ (5)
 #define LONG_DECREASE_FREQ (10)
 #define MAX_ALLOW_FREQ (99999999)
+/* user phrases used within this many keystrokes are boosted in candidates */
+#define RECENT_PHRASE_INTERVAL (4000)
 
 #define USER_UPDATE_FAIL (4)
 #define USER_UPDATE_INSERT (1)
 #define USER_UPDATE_MODIFY (2)
 #define USER_UPDATE_IGNORE (8)
+#define USER_UPDATE_REMOVE (16)
 
 typedef struct tag_UserPhraseData {
 	uint16_t *phoneSeq;
@@ -46,6 +49,50 @@ typedef struct tag_UserPhraseData {
  */
 int UserUpdatePhrase( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] );
 
+/**
+ * @brief Remove a phrase from the user phrase database.
+ *
+ * @param phoneSeq[] Phone sequence
+ * @param wordSeq[] Phrase against the phone sequence
+ *
+ * @return 1 if the phrase is removed, 0 if it is not a user phrase.
+ */
+int UserRemovePhrase( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] );
+
+/**
+ * @brief Remove user phrases until at most max phrases are left.
+ *
+ * Phrases which are not used for the longest time are removed first. Among
+ * them, the one with the lowest frequency is removed first. The phrases are
+ * sorted once, so the cost does not depend on how many are removed.
+ *
+ * @param max Maximum number of user phrases
+ *
+ * @return Number of phrases removed.
+ */
+int UserLimitPhrase( ChewingData *pgdata, int max );
+
+/**
+ * @brief Add or remove a phrase in the auto-learn suppression list.
+ *
+ * @param wordSeq[] Phrase which should not be learned automatically
+ * @param suppress 1 to add the phrase, 0 to remove it
+ *
+ * @return 0 on success, -1 on failure.
+ */
+int UserSuppressPhrase( ChewingData *pgdata, const char wordSeq[], int suppress );
+
+/**
+ * @brief Check if a phrase is in the auto-learn suppression list.
+ *
+ * @param wordSeq[] Phrase
+ *
+ * @return 1 if the phrase is suppressed, 0 otherwise.
+ */
+int UserIsPhraseSuppressed( ChewingData *pgdata, const char wordSeq[] );
+
+void TerminateUserPhraseSuppression( ChewingData *pgdata );
+
 /**
  * @brief Read the first phrase of the phone in user phrase database.
  *
@@ -20,21 +20,11 @@
 #define ZUIN_KEY_ERROR 4
 #define ZUIN_ERROR 8
 #define ZUIN_NO_WORD 16
+/** the syllable has no word and is replaced by a near-miss one */
+#define ZUIN_CORRECT 32
 
-/** keyboard layout */
-enum {
-	KB_DEFAULT,
-	KB_HSU,
-	KB_IBM,
-	KB_GIN_YIEH,
-	KB_ET,
-	KB_ET26,
-	KB_DVORAK,
-	KB_DVORAK_HSU,
-	KB_DACHEN_CP26,
-	KB_HANYU_PINYIN,
-	KB_TYPE_NUM
-};
+/** names of keyboard layouts, indexed by KB_* */
+extern char *kb_type_str[];
 
 int ZuinPhoInput( ChewingData *, ZuinData *,int key );  /* assume `key' is "ascii" code. */
 int ZuinRemoveLast( ZuinData * );
@@ -29,16 +29,26 @@ CHEWING_API int chewing_commit_Check( ChewingContext *ctx );
  * @brief Get current commit string regardless of current input state
  * @param ctx handle to Chewing IM context
  *
- * Alwasy returns a char pointer, caller must free it.
+ * Alwasy returns a char pointer, caller must free it with chewing_free().
  */
 CHEWING_API char *chewing_commit_String( ChewingContext *ctx );
 
+/**
+ * @brief Get current commit string regardless of current input state
+ * @param ctx handle to Chewing IM context
+ *
+ * The string is owned by ctx and is valid until the next call to this
+ * function with ctx.
+ */
+CHEWING_API const char *chewing_commit_String_static( ChewingContext *ctx );
+
 
 /*! \name Preedit string buffer
  */
 
 /*@{*/
 CHEWING_API char *chewing_buffer_String( ChewingContext *ctx );
+CHEWING_API const char *chewing_buffer_String_static( ChewingContext *ctx );
 CHEWING_API int chewing_buffer_Check( ChewingContext *ctx );
 CHEWING_API int chewing_buffer_Len( ChewingContext *ctx );
 /*@}*/
@@ -49,13 +59,34 @@ CHEWING_API int chewing_buffer_Len( ChewingContext *ctx );
  * @param ctx handle to Chewing IM context
  * @param[out] zuin_count pointer to the integer of available Zuin preedit string
  *
- * Always returns a C-style string (char pointer), caller must free it.
+ * Always returns a C-style string (char pointer), caller must free it with
+ * chewing_free().
  */
 CHEWING_API char *chewing_zuin_String( ChewingContext *ctx, int *zuin_count );
 
 CHEWING_API int chewing_zuin_Check( ChewingContext *ctx );
 /*@}*/
 
+
+/*! \name Bopomofo buffer
+ */
+
+/*@{*/
+/**
+ * @param ctx handle to Chewing IM context
+ * @retval TRUE if there are phonetic symbols in the bopomofo buffer
+ */
+CHEWING_API int chewing_bopomofo_Check( ChewingContext *ctx );
+
+/**
+ * @param ctx handle to Chewing IM context
+ *
+ * Returns the phonetic symbols in the bopomofo buffer. The string is owned
+ * by ctx and is valid until the next call to this function with ctx.
+ */
+CHEWING_API const char *chewing_bopomofo_String_static( ChewingContext *ctx );
+/*@}*/
+
 CHEWING_API int chewing_cursor_Current( ChewingContext *ctx );
 
 /*@{*/
@@ -67,6 +98,7 @@ CHEWING_API int chewing_cand_CurrentPage( ChewingContext *ctx );
 CHEWING_API void chewing_cand_Enumerate( ChewingContext *ctx );
 CHEWING_API int chewing_cand_hasNext( ChewingContext *ctx );
 CHEWING_API char *chewing_cand_String( ChewingContext *ctx );
+CHEWING_API const char *chewing_cand_String_static( ChewingContext *ctx );
 /*@}*/
 
 
@@ -76,16 +108,28 @@ CHEWING_API int chewing_interval_hasNext( ChewingContext *ctx );
 CHEWING_API void chewing_interval_Get( ChewingContext *ctx, IntervalType *it );
 /*@}*/
 
+
+/*! \name Offsets in the preedit string buffer
+ */
+
+/*@{*/
+CHEWING_API int chewing_buffer_CharToByte( ChewingContext *ctx, int char_offset );
+CHEWING_API int chewing_buffer_ByteToChar( ChewingContext *ctx, int byte_offset );
+CHEWING_API int chewing_buffer_PhoneToChar( ChewingContext *ctx, int phone_index );
+/*@}*/
+
 /*@{*/
 CHEWING_API int chewing_aux_Check( ChewingContext *ctx );
 CHEWING_API int chewing_aux_Length( ChewingContext *ctx );
 CHEWING_API char *chewing_aux_String( ChewingContext *ctx );
+CHEWING_API const char *chewing_aux_String_static( ChewingContext *ctx );
 /*@}*/
 
 
 /*@{*/
 CHEWING_API int chewing_keystroke_CheckIgnore( ChewingContext *ctx );
 CHEWING_API int chewing_keystroke_CheckAbsorb( ChewingContext *ctx );
+CHEWING_API int chewing_keystroke_CheckBell( ChewingContext *ctx );
 /*@}*/
 
 
@@ -94,6 +138,7 @@ CHEWING_API int chewing_kbtype_Total( ChewingContext *ctx );
 CHEWING_API void chewing_kbtype_Enumerate( ChewingContext *ctx );
 CHEWING_API int chewing_kbtype_hasNext( ChewingContext *ctx );
 CHEWING_API char *chewing_kbtype_String( ChewingContext *ctx );
+CHEWING_API const char *chewing_kbtype_String_static( ChewingContext *ctx );
 /*@}*/
 
 #endif /* CHEWING_MOD_AUX_H */
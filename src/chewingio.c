@@ -34,6 +34,7 @@
 #include "hash-private.h"
 #include "tree-private.h"
 #include "hanyupinyin-private.h"
+#include "key2pho-private.h"
 #include "private.h"
 #include "chewingio.h"
 #include "mod_aux.h"
@@ -88,7 +89,7 @@ const char * const PINYIN_FILES[] = {
 	NULL,
 };
 
-CHEWING_API int chewing_KBStr2Num( char str[] )
+CHEWING_API int chewing_KBStr2Num( const char str[] )
 {
 	int i;
 
@@ -117,20 +118,14 @@ static void chooseCandidate( ChewingContext *ctx, int toSelect, int key_buf_curs
 		if ( ! pgdata->bSelect ) {
 			ChoiceFirstAvail( pgdata );
 		} else {
-			if ( pgdata->config.bPhraseChoiceRearward ) {
-				int avail_willbe = (pgdata->availInfo.currentAvail > 0) ?
-					pgdata->availInfo.currentAvail - 1 :
-					pgdata->availInfo.nAvail - 1;
-				pgdata->chiSymbolCursor = pgdata->choiceInfo.oldChiSymbolCursor -
-					pgdata->availInfo.avail[ avail_willbe ].len;
-				if ( chewing_buffer_Len( ctx ) >
-						pgdata->choiceInfo.oldChiSymbolCursor ) {
-					pgdata->chiSymbolCursor++;
-				}
-			}
-			ChoiceNextAvail( pgdata );
+			/* the next phrase in the listing order, or the first one again */
+			int next = ChoiceAvailOrder( pgdata, pgdata->availInfo.currentAvail ) + 1;
+			if ( next >= pgdata->availInfo.nAvail )
+				next = 0;
+			ChoiceSetAvail( pgdata, ChoiceAvailOrder( pgdata, next ) );
 		}
-	} else if ( pgdata->symbolKeyBuf[ key_buf_cursor ] ) {
+	} else if ( key_buf_cursor < pgdata->chiSymbolBufLen &&
+	            pgdata->symbolKeyBuf[ key_buf_cursor ] ) {
 		/* Open Symbol Choice List */
 		if ( ! pgdata->choiceInfo.isSymbol )
 			OpenSymbolChoice( pgdata );
@@ -144,17 +139,42 @@ static ChewingData * allocate_ChewingData()
 		.selKey = { '1', '2', '3', '4', '5', '6', '7', '8', '9', '0' },
 	};
 
+	static const ChewingExtConfigData DEFAULT_EXT_CONFIG = {
+		.bNumpadAsSelection = 1,
+		.autoLearn = AUTOLEARN_ENABLED,
+	};
+
 	ChewingData *data = ALC( ChewingData, 1 );
 	if ( data ) {
 		data->config = DEFAULT_CONFIG;
+		data->ext_config = DEFAULT_EXT_CONFIG;
+#ifdef USE_BINARY_DATA
+		/* chewing_delete() may close them before they are opened */
+		plat_mmap_set_invalid( &data->static_data.char_phone_mmap );
+		plat_mmap_set_invalid( &data->static_data.char_begin_mmap );
+		plat_mmap_set_invalid( &data->static_data.char_mmap );
+		plat_mmap_set_invalid( &data->static_data.index_mmap );
+		plat_mmap_set_invalid( &data->static_data.dict_mmap );
+		plat_mmap_set_invalid( &data->static_data.tree_mmap );
+#endif
 	}
 
 	return data;
 }
 
-CHEWING_API ChewingContext *chewing_new()
+static void NullLogger(
+	void *data UNUSED, int level UNUSED, const char *fmt UNUSED, ... )
+{
+}
+
+CHEWING_API ChewingContext *chewing_new2(
+	const char *syspath,
+	const char *userpath,
+	void (*logger)( void *data, int level, const char *fmt, ... ),
+	void *loggerdata )
 {
 	ChewingContext *ctx;
+	ChewingData *pgdata;
 	int ret;
 	char search_path[PATH_MAX];
 	char path[PATH_MAX];
@@ -170,25 +190,44 @@ CHEWING_API ChewingContext *chewing_new()
 	ctx->data = allocate_ChewingData();
 	if ( !ctx->data )
 		goto error;
+	pgdata = ctx->data;
+
+	pgdata->logger = logger ? logger : NullLogger;
+	pgdata->loggerData = loggerdata;
 
 	chewing_Reset( ctx );
 
-	ret = get_search_path( search_path, sizeof( search_path ) );
-	if ( ret )
-		goto error;
+	if ( syspath ) {
+		strncpy( search_path, syspath, sizeof( search_path ) );
+		search_path[ sizeof( search_path ) - 1 ] = '\0';
+	}
+	else {
+		ret = get_search_path( search_path, sizeof( search_path ) );
+		if ( ret ) {
+			LOG_ERROR( "get_search_path returns %d", ret );
+			goto error;
+		}
+	}
 
 	ret = find_path_by_files(
 		search_path, CHAR_FILES, path, sizeof( path ) );
-	if ( ret )
+	if ( ret ) {
+		LOG_ERROR( "Cannot find character table in %s", search_path );
 		goto error;
+	}
 	ret = InitChar( ctx->data, path );
 	if ( ret )
 		goto error;
 
 	ret = find_path_by_files(
 		search_path, DICT_FILES, path, sizeof( path ) );
-	if ( ret )
+	if ( ret ) {
+		LOG_ERROR( "Cannot find dictionary in %s", search_path );
 		goto error;
+	}
+	strncpy( pgdata->static_data.dict_path, path,
+		sizeof( pgdata->static_data.dict_path ) );
+	pgdata->static_data.dict_path[ sizeof( pgdata->static_data.dict_path ) - 1 ] = '\0';
 	ret = InitDict( ctx->data, path );
 	if ( ret )
 		goto error;
@@ -197,30 +236,39 @@ CHEWING_API ChewingContext *chewing_new()
 		goto error;
 
 	// FIXME: Which return code indicate error?
-	ret = InitHash( ctx->data );
+	ret = InitHash( ctx->data, userpath );
+	/* do not fall back to another database if the given one cannot be used */
+	if ( ! ret && userpath )
+		goto error;
 
 	ctx->cand_no = 0;
 
 	ret = find_path_by_files(
 		search_path, SYMBOL_TABLE_FILES, path, sizeof( path ) );
-	if ( ret )
+	if ( ret ) {
+		LOG_ERROR( "Cannot find symbol table in %s", search_path );
 		goto error;
+	}
 	ret = InitSymbolTable( ctx->data, path );
 	if ( ret )
 		goto error;
 
 	ret = find_path_by_files(
 		search_path, EASY_SYMBOL_FILES, path, sizeof( path ) );
-	if ( ret )
+	if ( ret ) {
+		LOG_ERROR( "Cannot find easy symbol table in %s", search_path );
 		goto error;
+	}
 	ret = InitEasySymbolInput( ctx->data, path );
 	if ( ret )
 		goto error;
 
 	ret = find_path_by_files(
 		search_path, PINYIN_FILES, path, sizeof( path ) );
-	if ( ret )
+	if ( ret ) {
+		LOG_ERROR( "Cannot find pinyin table in %s", search_path );
 		goto error;
+	}
 	ret = InitHanyuPinYin( ctx->data, path );
 	if ( !ret )
 		goto error;
@@ -231,6 +279,11 @@ error:
 	return NULL;
 }
 
+CHEWING_API ChewingContext *chewing_new()
+{
+	return chewing_new2( NULL, NULL, NULL, NULL );
+}
+
 CHEWING_API int chewing_Init(
 		const char *dataPath UNUSED,
 		const char *hashPath UNUSED)
@@ -262,16 +315,29 @@ CHEWING_API int chewing_Init(
 
 CHEWING_API int chewing_Reset( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
+	ChewingData *pgdata;
 	ChewingStaticData static_data;
 	ChewingConfigData old_config;
+	ChewingExtConfigData old_ext_config;
+	void (*logger)( void *data, int level, const char *fmt, ... );
+	void *loggerData;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
 
 	/* Backup old config and restore it after clearing pgdata structure. */
 	old_config = pgdata->config;
+	old_ext_config = pgdata->ext_config;
 	static_data = pgdata->static_data;
+	logger = pgdata->logger;
+	loggerData = pgdata->loggerData;
 	memset( pgdata, 0, sizeof( ChewingData ) );
 	pgdata->config = old_config;
+	pgdata->ext_config = old_ext_config;
 	pgdata->static_data = static_data;
+	pgdata->logger = logger;
+	pgdata->loggerData = loggerData;
 
 	/* zuinData */
 	memset( &( pgdata->zuinData ), 0, sizeof( ZuinData ) );
@@ -294,10 +360,35 @@ CHEWING_API int chewing_Reset( ChewingContext *ctx )
 	return 0;
 }
 
+/*
+ * The Hsu selection key presets only make sense with the Hsu layouts, so
+ * other layouts keep the keys given by chewing_set_selKey().
+ */
+static void ApplyHsuSelKey( ChewingContext *ctx )
+{
+	static const int HSU_SELKEY[][ MAX_SELKEY ] = {
+		{ 'a', 's', 'd', 'f', 'j', 'k', 'l', '7', '8', '9' },
+		{ 'a', 's', 'd', 'f', 'z', 'x', 'c', 'v', '8', '9' },
+	};
+	int mode = ctx->data->config.hsuSelKeyType;
+
+	if ( ctx->data->zuinData.kbtype != KB_HSU &&
+	     ctx->data->zuinData.kbtype != KB_DVORAK_HSU )
+		return;
+	if ( mode != HSU_SELKEY_TYPE1 && mode != HSU_SELKEY_TYPE2 )
+		return;
+	chewing_set_selKey( ctx, (int *) HSU_SELKEY[ mode - HSU_SELKEY_TYPE1 ],
+		MAX_SELKEY );
+}
+
 CHEWING_API int chewing_set_KBType( ChewingContext *ctx, int kbtype )
 {
+	if ( !ctx )
+		return -1;
+
 	if ( kbtype < KB_TYPE_NUM && kbtype >= 0  ) {
 		ctx->data->zuinData.kbtype = kbtype;
+		ApplyHsuSelKey( ctx );
 		return 0;
 	} else {
 		ctx->data->zuinData.kbtype = KB_DEFAULT;
@@ -307,11 +398,17 @@ CHEWING_API int chewing_set_KBType( ChewingContext *ctx, int kbtype )
 
 CHEWING_API int chewing_get_KBType( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->zuinData.kbtype;
 }
 
 CHEWING_API char* chewing_get_KBString( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return NULL;
+
 	return strdup( kb_type_str[ ctx->data->zuinData.kbtype ] );
 }
 
@@ -330,6 +427,7 @@ CHEWING_API void chewing_delete( ChewingContext *ctx )
 			TerminateEasySymbolTable( ctx->data );
 			TerminateSymbolTable( ctx->data );
 			TerminateHash( ctx->data );
+			TerminateUserPhraseSuppression( ctx->data );
 			TerminateTree( ctx->data );
 			TerminateDict( ctx->data );
 			TerminateChar( ctx->data );
@@ -343,6 +441,33 @@ CHEWING_API void chewing_delete( ChewingContext *ctx )
 	return;
 }
 
+CHEWING_API void chewing_set_logger( ChewingContext *ctx,
+	void (*logger)( void *data, int level, const char *fmt, ... ),
+	void *data )
+{
+	if ( !ctx )
+		return;
+
+	ctx->data->logger = logger ? logger : NullLogger;
+	ctx->data->loggerData = data;
+}
+
+CHEWING_API const char *chewing_dict_path_Get( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return NULL;
+
+	return ctx->data->static_data.dict_path;
+}
+
+CHEWING_API const char *chewing_userphrase_path_Get( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return NULL;
+
+	return ctx->data->static_data.hashfilename;
+}
+
 CHEWING_API void chewing_free( void *p )
 {
 	if ( p )
@@ -352,6 +477,9 @@ CHEWING_API void chewing_free( void *p )
 
 CHEWING_API int chewing_Configure( ChewingContext *ctx, ChewingConfigData *pcd )
 {
+	if ( !ctx )
+		return -1;
+
 	chewing_set_candPerPage( ctx, pcd->candPerPage );
 	chewing_set_maxChiSymbolLen( ctx, pcd->maxChiSymbolLen );
 	chewing_set_selKey( ctx, pcd->selKey, MAX_SELKEY );
@@ -367,37 +495,61 @@ CHEWING_API int chewing_Configure( ChewingContext *ctx, ChewingConfigData *pcd )
 
 CHEWING_API void chewing_set_candPerPage( ChewingContext *ctx, int n )
 {
+	if ( !ctx )
+		return;
+
 	if ( MIN_SELKEY <= n && n <= MAX_SELKEY )
 		ctx->data->config.candPerPage = n;
 }
 
 CHEWING_API int chewing_get_candPerPage( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.candPerPage;
 }
 
 CHEWING_API void chewing_set_maxChiSymbolLen( ChewingContext *ctx, int n )
 {
-	ctx->data->config.maxChiSymbolLen = n;
+	if ( !ctx )
+		return;
+
+	if ( MIN_CHI_SYMBOL_LEN <= n && n <= MAX_CHI_SYMBOL_LEN )
+		ctx->data->config.maxChiSymbolLen = n;
 }
 
 CHEWING_API int chewing_get_maxChiSymbolLen( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.maxChiSymbolLen;
 }
 
-CHEWING_API void chewing_set_selKey( ChewingContext *ctx, int *selkeys,
-                                     int len UNUSED)
+CHEWING_API void chewing_set_selKey( ChewingContext *ctx, int *selkeys, int len )
 {
+	if ( !ctx )
+		return;
+
+	if ( ! selkeys || len < MIN_SELKEY || len > MAX_SELKEY )
+		return;
+
+	memset( ctx->data->config.selKey, 0, sizeof( ctx->data->config.selKey ) );
 	memcpy(
 		ctx->data->config.selKey,
 		selkeys,
-		sizeof( selkeys[ 0 ] ) * MAX_SELKEY );
+		sizeof( selkeys[ 0 ] ) * len );
 }
 
 CHEWING_API int* chewing_get_selKey( ChewingContext *ctx )
 {
-	int *selkeys = ALC( int , MAX_SELKEY );
+	int *selkeys;
+
+	if ( !ctx )
+		return NULL;
+
+	selkeys = ALC( int , MAX_SELKEY );
 	if ( selkeys ) {
 		memcpy( selkeys, ctx->data->config.selKey,
 			sizeof( *selkeys ) * MAX_SELKEY );
@@ -407,6 +559,9 @@ CHEWING_API int* chewing_get_selKey( ChewingContext *ctx )
 
 CHEWING_API void chewing_set_addPhraseDirection( ChewingContext *ctx, int direction )
 {
+	if ( !ctx )
+		return;
+
 	ctx->data->config.bAddPhraseForward = direction;
 	if ( (ctx->data->config.bAddPhraseForward != 0) && (ctx->data->config.bAddPhraseForward != 1) )
 		ctx->data->config.bAddPhraseForward = 0;
@@ -414,11 +569,17 @@ CHEWING_API void chewing_set_addPhraseDirection( ChewingContext *ctx, int direct
 
 CHEWING_API int chewing_get_addPhraseDirection( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.bAddPhraseForward;
 }
 
 CHEWING_API void chewing_set_spaceAsSelection( ChewingContext *ctx, int mode )
 {
+	if ( !ctx )
+		return;
+
 	ctx->data->config.bSpaceAsSelection = mode;
 	if ( (ctx->data->config.bSpaceAsSelection != 0) && (ctx->data->config.bSpaceAsSelection != 1) )
 		ctx->data->config.bSpaceAsSelection = 1;
@@ -426,11 +587,330 @@ CHEWING_API void chewing_set_spaceAsSelection( ChewingContext *ctx, int mode )
 
 CHEWING_API int chewing_get_spaceAsSelection( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.bSpaceAsSelection;
 }
 
+CHEWING_API void chewing_set_numpadAsSelection( ChewingContext *ctx, int mode )
+{
+	if ( !ctx )
+		return;
+
+	ctx->data->ext_config.bNumpadAsSelection = mode;
+	if ( (ctx->data->ext_config.bNumpadAsSelection != 0) && (ctx->data->ext_config.bNumpadAsSelection != 1) )
+		ctx->data->ext_config.bNumpadAsSelection = 1;
+}
+
+CHEWING_API int chewing_get_numpadAsSelection( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return ctx->data->ext_config.bNumpadAsSelection;
+}
+
+CHEWING_API void chewing_set_recentPhraseFirst( ChewingContext *ctx, int mode )
+{
+	if ( !ctx )
+		return;
+
+	ctx->data->ext_config.bRecentPhraseFirst = mode;
+	if ( (ctx->data->ext_config.bRecentPhraseFirst != 0) && (ctx->data->ext_config.bRecentPhraseFirst != 1) )
+		ctx->data->ext_config.bRecentPhraseFirst = 1;
+}
+
+CHEWING_API int chewing_get_recentPhraseFirst( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return ctx->data->ext_config.bRecentPhraseFirst;
+}
+
+CHEWING_API void chewing_set_autoPairSymbol( ChewingContext *ctx, int mode )
+{
+	if ( !ctx )
+		return;
+
+	ctx->data->ext_config.bAutoPairSymbol = mode;
+	if ( (ctx->data->ext_config.bAutoPairSymbol != 0) && (ctx->data->ext_config.bAutoPairSymbol != 1) )
+		ctx->data->ext_config.bAutoPairSymbol = 1;
+}
+
+CHEWING_API int chewing_get_autoPairSymbol( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return ctx->data->ext_config.bAutoPairSymbol;
+}
+
+CHEWING_API void chewing_set_autoLearn( ChewingContext *ctx, int mode )
+{
+	if ( !ctx )
+		return;
+
+	if ( mode == AUTOLEARN_ENABLED || mode == AUTOLEARN_DISABLED )
+		ctx->data->ext_config.autoLearn = mode;
+}
+
+CHEWING_API int chewing_get_autoLearn( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return ctx->data->ext_config.autoLearn;
+}
+
+CHEWING_API int chewing_set_autoLearnSuppression( ChewingContext *ctx, const char *phrase, int suppress )
+{
+	if ( !ctx )
+		return -1;
+
+	if ( ! phrase || ! *phrase )
+		return -1;
+	return UserSuppressPhrase( ctx->data, phrase, suppress != 0 );
+}
+
+CHEWING_API int chewing_get_autoLearnSuppression( ChewingContext *ctx, const char *phrase )
+{
+	if ( !ctx )
+		return -1;
+
+	if ( ! phrase )
+		return 0;
+	return UserIsPhraseSuppressed( ctx->data, phrase );
+}
+
+/*
+ * Convert space separated bopomofo string like "ㄘㄜˋ ㄕˋ" to phone sequence.
+ *
+ * return the length of phoneSeq, or -1 if bopomofo is invalid.
+ */
+static int PhoneSeqFromBopomofo( uint16_t phoneSeq[], int max_len, const char *bopomofo )
+{
+	char buf[ ZUIN_SIZE * MAX_UTF8_SIZE + 1 ];
+	char zhuin[ ZUIN_SIZE * MAX_UTF8_SIZE + 1 ];
+	const char *end;
+	size_t len;
+	int n = 0;
+
+	while ( *bopomofo ) {
+		if ( *bopomofo == ' ' ) {
+			++bopomofo;
+			continue;
+		}
+		end = strchr( bopomofo, ' ' );
+		len = end ? (size_t) ( end - bopomofo ) : strlen( bopomofo );
+		if ( n >= max_len || len >= sizeof( buf ) )
+			return -1;
+		memcpy( buf, bopomofo, len );
+		buf[ len ] = '\0';
+
+		/* UintFromPhone() skips unknown symbols, so check the round trip */
+		phoneSeq[ n ] = UintFromPhone( buf );
+		if ( ! PhoneFromUint( zhuin, sizeof( zhuin ), phoneSeq[ n ] ) ||
+		     strcmp( zhuin, buf ) )
+			return -1;
+
+		++n;
+		bopomofo += len;
+	}
+	phoneSeq[ n ] = 0;
+	return n;
+}
+
+/*
+ * Convert phone sequence to space separated bopomofo string.
+ *
+ * return the length of the string, or -1 if buf is too small.
+ */
+static int BopomofoFromPhoneSeq( char *buf, size_t buf_len, const uint16_t phoneSeq[] )
+{
+	char zhuin[ ZUIN_SIZE * MAX_UTF8_SIZE + 1 ];
+	size_t len = 0, n;
+	int i;
+
+	buf[ 0 ] = '\0';
+	for ( i = 0; phoneSeq[ i ] != 0; i++ ) {
+		if ( ! PhoneFromUint( zhuin, sizeof( zhuin ), phoneSeq[ i ] ) )
+			continue;
+		n = strlen( zhuin );
+		if ( len + ( len > 0 ) + n >= buf_len )
+			return -1;
+		if ( len > 0 )
+			buf[ len++ ] = ' ';
+		memcpy( buf + len, zhuin, n + 1 );
+		len += n;
+	}
+	return len;
+}
+
+/* Parse the arguments of chewing_userphrase_add/remove/lookup */
+static int UserPhraseFromArgs( uint16_t phoneSeq[], const char *phrase, const char *bopomofo )
+{
+	int len;
+
+	if ( ! phrase || ! bopomofo || ! isValidChineseString( phrase ) )
+		return 0;
+	len = ueStrLen( phrase );
+	if ( len > MAX_PHRASE_LEN )
+		return 0;
+	return PhoneSeqFromBopomofo( phoneSeq, MAX_PHRASE_LEN, bopomofo ) == len;
+}
+
+CHEWING_API int chewing_userphrase_enumerate( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	ctx->data->static_data.userphrase_enum = HashNext( ctx->data, NULL );
+	return 0;
+}
+
+CHEWING_API int chewing_userphrase_has_next(
+	ChewingContext *ctx,
+	unsigned int *phrase_len,
+	unsigned int *bopomofo_len )
+{
+	HASH_ITEM *pItem;
+	char bopomofo[ MAX_PHONE_SEQ_LEN * ( ZUIN_SIZE * MAX_UTF8_SIZE + 1 ) ];
+	int len;
+
+	if ( !ctx )
+		return 0;
+	pItem = ctx->data->static_data.userphrase_enum;
+
+	if ( ! pItem )
+		return 0;
+
+	if ( phrase_len )
+		*phrase_len = strlen( pItem->data.wordSeq ) + 1;
+	if ( bopomofo_len ) {
+		len = BopomofoFromPhoneSeq(
+			bopomofo, sizeof( bopomofo ), pItem->data.phoneSeq );
+		*bopomofo_len = len < 0 ? 0 : len + 1;
+	}
+	return 1;
+}
+
+CHEWING_API int chewing_userphrase_get(
+	ChewingContext *ctx,
+	char *phrase_buf, unsigned int phrase_len,
+	char *bopomofo_buf, unsigned int bopomofo_len )
+{
+	HASH_ITEM *pItem;
+
+	if ( !ctx )
+		return -1;
+	pItem = ctx->data->static_data.userphrase_enum;
+
+	if ( ! pItem || ! phrase_buf || ! bopomofo_buf || bopomofo_len == 0 )
+		return -1;
+	if ( strlen( pItem->data.wordSeq ) >= phrase_len )
+		return -1;
+	if ( BopomofoFromPhoneSeq(
+		bopomofo_buf, bopomofo_len, pItem->data.phoneSeq ) < 0 )
+		return -1;
+
+	strcpy( phrase_buf, pItem->data.wordSeq );
+	ctx->data->static_data.userphrase_enum = HashNext( ctx->data, pItem );
+	return 0;
+}
+
+CHEWING_API int chewing_userphrase_add(
+	ChewingContext *ctx,
+	const char *phrase_buf,
+	const char *bopomofo_buf )
+{
+	uint16_t phoneSeq[ MAX_PHRASE_LEN + 1 ];
+
+	if ( !ctx )
+		return -1;
+
+	if ( ! UserPhraseFromArgs( phoneSeq, phrase_buf, bopomofo_buf ) )
+		return 0;
+	if ( HashFindEntry( ctx->data, phoneSeq, phrase_buf ) )
+		return 0;
+	return UserUpdatePhrase( ctx->data, phoneSeq, phrase_buf ) == USER_UPDATE_INSERT;
+}
+
+CHEWING_API int chewing_userphrase_remove(
+	ChewingContext *ctx,
+	const char *phrase_buf,
+	const char *bopomofo_buf )
+{
+	uint16_t phoneSeq[ MAX_PHRASE_LEN + 1 ];
+
+	if ( !ctx )
+		return -1;
+
+	if ( ! UserPhraseFromArgs( phoneSeq, phrase_buf, bopomofo_buf ) )
+		return 0;
+	return UserRemovePhrase( ctx->data, phoneSeq, phrase_buf );
+}
+
+CHEWING_API int chewing_userphrase_lookup(
+	ChewingContext *ctx,
+	const char *phrase_buf,
+	const char *bopomofo_buf )
+{
+	uint16_t phoneSeq[ MAX_PHRASE_LEN + 1 ];
+
+	if ( !ctx )
+		return -1;
+
+	if ( ! UserPhraseFromArgs( phoneSeq, phrase_buf, bopomofo_buf ) )
+		return 0;
+	return HashFindEntry( ctx->data, phoneSeq, phrase_buf ) != NULL;
+}
+
+CHEWING_API void chewing_set_maxUserPhrase( ChewingContext *ctx, int n )
+{
+	if ( !ctx )
+		return;
+
+	if ( n < 0 )
+		return;
+
+	ctx->data->ext_config.maxUserPhrase = n;
+	if ( n > 0 )
+		UserLimitPhrase( ctx->data, n );
+}
+
+CHEWING_API int chewing_get_maxUserPhrase( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return ctx->data->ext_config.maxUserPhrase;
+}
+
+CHEWING_API void chewing_set_phoneCorrection( ChewingContext *ctx, int mode )
+{
+	if ( !ctx )
+		return;
+
+	ctx->data->ext_config.bPhoneCorrection = mode;
+	if ( (ctx->data->ext_config.bPhoneCorrection != 0) && (ctx->data->ext_config.bPhoneCorrection != 1) )
+		ctx->data->ext_config.bPhoneCorrection = 1;
+}
+
+CHEWING_API int chewing_get_phoneCorrection( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return ctx->data->ext_config.bPhoneCorrection;
+}
+
 CHEWING_API void chewing_set_escCleanAllBuf( ChewingContext *ctx, int mode )
 {
+	if ( !ctx )
+		return;
+
 	ctx->data->config.bEscCleanAllBuf = mode;
 	if ( (ctx->data->config.bEscCleanAllBuf != 0) && (ctx->data->config.bEscCleanAllBuf != 1) )
 		ctx->data->config.bEscCleanAllBuf = 0;
@@ -438,66 +918,132 @@ CHEWING_API void chewing_set_escCleanAllBuf( ChewingContext *ctx, int mode )
 
 CHEWING_API int chewing_get_escCleanAllBuf( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.bEscCleanAllBuf;
 }
 
 CHEWING_API void chewing_set_hsuSelKeyType( ChewingContext *ctx, int mode )
 {
-	ctx->data->config.hsuSelKeyType = mode;
+	if ( !ctx )
+		return;
+
+	if ( mode == HSU_SELKEY_TYPE1 || mode == HSU_SELKEY_TYPE2 ) {
+		ctx->data->config.hsuSelKeyType = mode;
+		ApplyHsuSelKey( ctx );
+	}
 }
 
 CHEWING_API int chewing_get_hsuSelKeyType( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.hsuSelKeyType;
 }
 
 CHEWING_API void chewing_set_autoShiftCur( ChewingContext *ctx, int mode )
 {
+	if ( !ctx )
+		return;
+
 	ctx->data->config.bAutoShiftCur = mode;
+	if ( (ctx->data->config.bAutoShiftCur != 0) && (ctx->data->config.bAutoShiftCur != 1) )
+		ctx->data->config.bAutoShiftCur = 1;
 }
 
 CHEWING_API int chewing_get_autoShiftCur( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.bAutoShiftCur;
 }
 
 CHEWING_API void chewing_set_easySymbolInput( ChewingContext *ctx, int mode )
 {
+	if ( !ctx )
+		return;
+
 	ctx->data->config.bEasySymbolInput = mode;
+	if ( (ctx->data->config.bEasySymbolInput != 0) && (ctx->data->config.bEasySymbolInput != 1) )
+		ctx->data->config.bEasySymbolInput = 1;
 }
 
 CHEWING_API int chewing_get_easySymbolInput( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.bEasySymbolInput;
 }
 
 CHEWING_API void chewing_set_phraseChoiceRearward( ChewingContext *ctx, int mode )
 {
+	if ( !ctx )
+		return;
+
 	ctx->data->config.bPhraseChoiceRearward = mode;
+	if ( (ctx->data->config.bPhraseChoiceRearward != 0) && (ctx->data->config.bPhraseChoiceRearward != 1) )
+		ctx->data->config.bPhraseChoiceRearward = 1;
 }
 
 CHEWING_API int chewing_get_phraseChoiceRearward( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->config.bPhraseChoiceRearward;
 }
 
+CHEWING_API void chewing_set_shortestPhraseFirst( ChewingContext *ctx, int mode )
+{
+	if ( !ctx )
+		return;
+
+	ctx->data->ext_config.bShortestPhraseFirst = mode;
+	if ( (ctx->data->ext_config.bShortestPhraseFirst != 0) && (ctx->data->ext_config.bShortestPhraseFirst != 1) )
+		ctx->data->ext_config.bShortestPhraseFirst = 1;
+}
+
+CHEWING_API int chewing_get_shortestPhraseFirst( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return ctx->data->ext_config.bShortestPhraseFirst;
+}
+
 CHEWING_API void chewing_set_ChiEngMode( ChewingContext *ctx, int mode )
 {
+	if ( !ctx )
+		return;
+
 	ctx->data->bChiSym = ( mode == CHINESE_MODE ? 1 : 0 );
 }
 
 CHEWING_API int chewing_get_ChiEngMode( ChewingContext *ctx ) 
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->bChiSym;
 }
 
 CHEWING_API void chewing_set_ShapeMode( ChewingContext *ctx, int mode )
 {
+	if ( !ctx )
+		return;
+
 	ctx->data->bFullShape = (mode == FULLSHAPE_MODE ? 1 : 0);
 }
 
 CHEWING_API int chewing_get_ShapeMode( ChewingContext *ctx ) 
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->bFullShape;
 }
 
@@ -509,38 +1055,48 @@ static void CheckAndResetRange( ChewingData *pgdata )
 	}
 }
 
+/* num is the index of the candidate in the whole list */
+static int SelectCandidate( ChewingData *pgdata, int num )
+{
+	if ( num < 0 || num >= pgdata->choiceInfo.nTotalChoice )
+		return 0;
+
+	if ( pgdata->choiceInfo.isSymbol ) {
+		SymbolChoice( pgdata, num );
+	}
+	else { 
+		/* change the select interval & selectStr & nSelect */
+		AddSelect( pgdata, num );
+		/* second, call choice module */
+		ChoiceSelect( pgdata, num );
+		/* automatically shift the cursor to next phrase */
+		if ( pgdata->config.bAutoShiftCur != 0 &&
+		     /* if cursor at end of string, do not shift the cursor. */
+		     pgdata->chiSymbolCursor < pgdata->chiSymbolBufLen ) {
+			int len = pgdata->availInfo.avail[
+				pgdata->availInfo.currentAvail ].len;
+			pgdata->chiSymbolCursor += len;
+		}
+	}
+	return 1;
+}
+
 static int DoSelect( ChewingData *pgdata, int num )
 {
 	if ( num >= 0 ) {
 		num += pgdata->choiceInfo.pageNo * pgdata->choiceInfo.nChoicePerPage;
-		/* Note: if num is larger than the total, there will be big troubles. */
-		if ( num < pgdata->choiceInfo.nTotalChoice ) {
-			if ( pgdata->choiceInfo.isSymbol ) {
-				SymbolChoice( pgdata, num );
-			}
-			else { 
-				/* change the select interval & selectStr & nSelect */
-				AddSelect( pgdata, num );
-				/* second, call choice module */
-				ChoiceSelect( pgdata, num );
-				/* automatically shift the cursor to next phrase */
-				if ( pgdata->config.bAutoShiftCur != 0 &&
-				     /* if cursor at end of string, do not shift the cursor. */
-				     pgdata->chiSymbolCursor < pgdata->chiSymbolBufLen ) {
-					int len = pgdata->availInfo.avail[
-						pgdata->availInfo.currentAvail ].len;
-					pgdata->chiSymbolCursor += len;
-				}
-			}
-			return 1;
-		}
+		return SelectCandidate( pgdata, num );
 	}
 	return 0;
 }
 
 CHEWING_API int chewing_handle_Space( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
+	ChewingData *pgdata;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
 
 	/*
 	 * Use chewing_handle_Default( ctx, ' ' ) to handle space when:
@@ -566,10 +1122,15 @@ CHEWING_API int chewing_handle_Space( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Esc( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
@@ -592,11 +1153,17 @@ CHEWING_API int chewing_handle_Esc( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Enter( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
-	int nCommitStr = pgdata->chiSymbolBufLen;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
+	int nCommitStr;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+	nCommitStr = pgdata->chiSymbolBufLen;
+
 	if ( ! ChewingIsEntering( pgdata ) ) {
 		keystrokeRtn = KEYSTROKE_IGNORE;
 	}
@@ -642,10 +1209,15 @@ CHEWING_API int chewing_handle_Enter( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Del( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
@@ -663,16 +1235,29 @@ CHEWING_API int chewing_handle_Del( ChewingContext *ctx )
 		}
 		CallPhrasing( pgdata );
 	}
+	else if ( ! pgdata->choiceInfo.isSymbol ) {
+		/* the next selection key removes the user phrase */
+		pgdata->choiceInfo.bRemoveUserPhrase = 1;
+		SetUpdatePhraseMsg( pgdata, "", 0, USER_UPDATE_REMOVE );
+		MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
+		MakeOutputAddMsgAndCleanInterval( pgo, pgdata );
+		return 0;
+	}
 	MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
 	return 0;
 }
 
 CHEWING_API int chewing_handle_Backspace( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
@@ -698,11 +1283,16 @@ CHEWING_API int chewing_handle_Backspace( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Up( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 	int key_buf_cursor;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
@@ -710,7 +1300,7 @@ CHEWING_API int chewing_handle_Up( ChewingContext *ctx )
 	}
 
 	key_buf_cursor = pgdata->chiSymbolCursor;
-	if ( pgdata->chiSymbolCursor == pgdata->chiSymbolBufLen )
+	if ( key_buf_cursor > 0 && pgdata->chiSymbolCursor == pgdata->chiSymbolBufLen )
 		key_buf_cursor--;
 
 	if ( ! pgdata->symbolKeyBuf[ key_buf_cursor ] ) {
@@ -724,12 +1314,17 @@ CHEWING_API int chewing_handle_Up( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Down( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int toSelect = 0;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 	int key_buf_cursor;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
@@ -737,7 +1332,7 @@ CHEWING_API int chewing_handle_Down( ChewingContext *ctx )
 	}
 
 	key_buf_cursor = pgdata->chiSymbolCursor;
-	if ( pgdata->chiSymbolCursor == pgdata->chiSymbolBufLen )
+	if ( key_buf_cursor > 0 && pgdata->chiSymbolCursor == pgdata->chiSymbolBufLen )
 		key_buf_cursor--;
 
 	/* see if to select */
@@ -746,6 +1341,11 @@ CHEWING_API int chewing_handle_Down( ChewingContext *ctx )
 
 	chooseCandidate( ctx, toSelect, key_buf_cursor );
 
+	/* no candidate for the character at cursor */
+	if ( ChewingIsEntering( pgdata ) && ! pgdata->bSelect ) {
+		keystrokeRtn = KEYSTROKE_ABSORB | KEYSTROKE_BELL;
+	}
+
 	MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
 	return 0;
 }
@@ -753,10 +1353,15 @@ CHEWING_API int chewing_handle_Down( ChewingContext *ctx )
 /* Add phrase in Hanin Style */
 CHEWING_API int chewing_handle_ShiftLeft( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	if ( ! ChewingIsEntering( pgdata ) ) {
 		keystrokeRtn = KEYSTROKE_IGNORE;
 	} 
@@ -784,10 +1389,15 @@ CHEWING_API int chewing_handle_ShiftLeft( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Left( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	if ( ! ChewingIsEntering( pgdata ) ) {
 		keystrokeRtn = KEYSTROKE_IGNORE;
 	}
@@ -813,10 +1423,15 @@ CHEWING_API int chewing_handle_Left( ChewingContext *ctx )
 /* Add phrase in Hanin Style */
 CHEWING_API int chewing_handle_ShiftRight( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	if ( ! ChewingIsEntering( pgdata ) ) {
 		keystrokeRtn = KEYSTROKE_IGNORE;
 	} 
@@ -845,10 +1460,15 @@ CHEWING_API int chewing_handle_ShiftRight( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Right( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	if ( ! ChewingIsEntering( pgdata ) ) {
 		keystrokeRtn = KEYSTROKE_IGNORE;
 	}
@@ -875,10 +1495,15 @@ CHEWING_API int chewing_handle_Right( ChewingContext *ctx )
 CHEWING_API int chewing_handle_Tab( ChewingContext *ctx )
 {
 	int cursor;
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
@@ -910,10 +1535,15 @@ CHEWING_API int chewing_handle_Tab( ChewingContext *ctx )
 CHEWING_API int chewing_handle_DblTab( ChewingContext *ctx )
 {
 	int cursor;
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
@@ -934,8 +1564,13 @@ CHEWING_API int chewing_handle_DblTab( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Capslock( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
 
 	pgdata->bChiSym = 1 - pgdata->bChiSym;
 	pgdata->bCaseChange = ( pgdata->bChiSym == CHINESE_MODE ? 0 : 1 );
@@ -945,10 +1580,15 @@ CHEWING_API int chewing_handle_Capslock( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Home( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) { 
@@ -963,10 +1603,15 @@ CHEWING_API int chewing_handle_Home( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_End( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
@@ -981,36 +1626,58 @@ CHEWING_API int chewing_handle_End( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_PageUp( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
 		keystrokeRtn = KEYSTROKE_IGNORE;
 	}
-	else if ( ! pgdata->bSelect ) { 
+	else if ( pgdata->bSelect ) {
+		if ( pgdata->choiceInfo.pageNo > 0 )
+			pgdata->choiceInfo.pageNo--;
+		else
+			pgdata->choiceInfo.pageNo = pgdata->choiceInfo.nPage - 1;
+	}
+	else {
 		pgdata->chiSymbolCursor = pgdata->chiSymbolBufLen;
-	} 
+	}
 	MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
 	return 0;
 }
 
 CHEWING_API int chewing_handle_PageDown( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( ! ChewingIsEntering( pgdata ) ) {
 		keystrokeRtn = KEYSTROKE_IGNORE;
 	}
-	else if ( ! pgdata->bSelect ) { 
+	else if ( pgdata->bSelect ) {
+		if ( pgdata->choiceInfo.pageNo < pgdata->choiceInfo.nPage - 1 )
+			pgdata->choiceInfo.pageNo++;
+		else
+			pgdata->choiceInfo.pageNo = 0;
+	}
+	else {
 		pgdata->chiSymbolCursor = pgdata->chiSymbolBufLen;
-	} 
+	}
 	MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
 	return 0;
 }
@@ -1047,11 +1714,17 @@ static int dvorak_convert( int key )
 
 CHEWING_API int chewing_handle_Default( ChewingContext *ctx, int key )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int rtn, num;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 	int bQuickCommit = 0;
+	int bCorrected = 0;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
 
 	/* Update lifetime */
 	ctx->data->static_data.chewing_lifetime++;
@@ -1063,8 +1736,10 @@ CHEWING_API int chewing_handle_Default( ChewingContext *ctx, int key )
 	}
 
 	/* We ignore non-printable input */
-	if ( ! isprint( key ) )
+	if ( ! isprint( key ) ) {
+		keystrokeRtn = KEYSTROKE_IGNORE;
 		goto End_KeyDefault;
+	}
 
 	CheckAndResetRange( pgdata );
 
@@ -1078,12 +1753,32 @@ CHEWING_API int chewing_handle_Default( ChewingContext *ctx, int key )
 
 	/* selecting */
 	if ( pgdata->bSelect ) {
+		if ( pgdata->choiceInfo.bRemoveUserPhrase ) {
+			num = CountSelKeyNum( key, pgdata );
+			if ( num < 0 ) {
+				/* removal is cancelled */
+				pgdata->choiceInfo.bRemoveUserPhrase = 0;
+				goto End_Paging;
+			}
+			num += pgdata->choiceInfo.pageNo * pgdata->choiceInfo.nChoicePerPage;
+			if ( ! ChoiceRemoveUserPhrase( pgdata, num ) ) {
+				/* not a user phrase */
+				keystrokeRtn = KEYSTROKE_ABSORB | KEYSTROKE_BELL;
+				goto End_Paging;
+			}
+			MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
+			MakeOutputAddMsgAndCleanInterval( pgo, pgdata );
+			return 0;
+		}
 		if ( key == ' ' )
 			return chewing_handle_Right( ctx );
 		/* num starts from 0 */
 		num = CountSelKeyNum( key, pgdata );
 		if ( num >= 0 ) {
-			DoSelect( pgdata, num );
+			if ( ! DoSelect( pgdata, num ) ) {
+				/* no candidate for this selection key */
+				keystrokeRtn = KEYSTROKE_ABSORB | KEYSTROKE_BELL;
+			}
 			goto End_keyproc;
 		}
 		
@@ -1115,6 +1810,8 @@ CHEWING_API int chewing_handle_Default( ChewingContext *ctx, int key )
 				}
 				goto End_Paging;
 			default:
+				/* invalid key in selection mode */
+				keystrokeRtn = KEYSTROKE_ABSORB | KEYSTROKE_BELL;
 				break;
 		}
 	}
@@ -1142,8 +1839,11 @@ CHEWING_API int chewing_handle_Default( ChewingContext *ctx, int key )
 				rtn );
 			DEBUG_FLUSH;
 			
-			if ( rtn == ZUIN_KEY_ERROR )
+			if ( rtn == ZUIN_KEY_ERROR ) {
 				rtn = SpecialSymbolInput( key, pgdata );
+				if ( rtn == ZUIN_ABSORB && pgdata->ext_config.bAutoPairSymbol )
+					SpecialSymbolPair( key, pgdata );
+			}
 			switch ( rtn ) {
 				case ZUIN_ABSORB:
 					keystrokeRtn = KEYSTROKE_ABSORB;
@@ -1151,6 +1851,11 @@ CHEWING_API int chewing_handle_Default( ChewingContext *ctx, int key )
 				case ZUIN_COMMIT:
 					AddChi( pgdata->zuinData.phone, pgdata );
 					break;
+				case ZUIN_CORRECT:
+					AddChi( pgdata->zuinData.phone, pgdata );
+					SetCorrectPhoneMsg( pgdata, pgdata->zuinData.phone );
+					bCorrected = 1;
+					break;
 				case ZUIN_NO_WORD:
 					keystrokeRtn = KEYSTROKE_BELL | KEYSTROKE_ABSORB;
 					break;
@@ -1245,13 +1950,15 @@ End_KeyDefault:
 	CallPhrasing( pgdata );
 End_Paging:
 	MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
+	if ( bCorrected )
+		MakeOutputAddMsgAndCleanInterval( pgo, pgdata );
 	return 0;
 }
 
 CHEWING_API int chewing_handle_CtrlNum( ChewingContext *ctx, int key )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 	int newPhraseLen;
 	int i;
@@ -1260,6 +1967,11 @@ CHEWING_API int chewing_handle_CtrlNum( ChewingContext *ctx, int key )
 	int phraseState;
 	int cursor;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	CheckAndResetRange( pgdata );
 
 	if ( pgdata->bSelect )
@@ -1367,12 +2079,19 @@ CHEWING_API int chewing_handle_CtrlOption( ChewingContext *ctx, int key )
 
 CHEWING_API int chewing_handle_ShiftSpace( ChewingContext *ctx )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
 
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
 	if ( ! pgdata->bSelect ) {
 		CheckAndResetRange( pgdata );
+		/* toggle between full shape and half shape */
+		pgdata->bFullShape = ! pgdata->bFullShape;
 	}
 	CallPhrasing( pgdata );
 	MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
@@ -1381,11 +2100,23 @@ CHEWING_API int chewing_handle_ShiftSpace( ChewingContext *ctx )
 
 CHEWING_API int chewing_handle_Numlock( ChewingContext *ctx, int key )
 {
-	ChewingData *pgdata = ctx->data;
-	ChewingOutput *pgo = ctx->output;
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
 	int rtn, QuickCommit = 0;
 	int keystrokeRtn = KEYSTROKE_ABSORB;
-	
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
+	if ( pgdata->bSelect && ! pgdata->ext_config.bNumpadAsSelection ) {
+		/* Numpad keys are not selection keys, so close the candidate
+		 * window and handle them as normal numeric input.
+		 */
+		ChoiceEndChoice( pgdata );
+	}
+
 	if ( ! pgdata->bSelect ) {
 		/* If we're not selecting words, we should send out numeric
 		 * characters at once. 
@@ -1417,20 +2148,214 @@ CHEWING_API int chewing_handle_Numlock( ChewingContext *ctx, int key )
 		 * and submit the words. 
 		 */
 		int num = -1;
-		if ( key > '0' && key < '9' )
+		if ( key > '0' && key <= '9' )
 			num = key - '1';
 		else if ( key == '0' )
 			num = 9;
-		DoSelect( pgdata, num );
+		if ( ! DoSelect( pgdata, num ) ) {
+			/* no candidate for this selection key */
+			keystrokeRtn = KEYSTROKE_ABSORB | KEYSTROKE_BELL;
+		}
 	}
 	CallPhrasing( pgdata );
 	MakeOutputWithRtn( pgo, pgdata, keystrokeRtn );
 	return 0;
 }
 
+CHEWING_API int chewing_commit_preedit_buf( ChewingContext *ctx )
+{
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
+	int nCommitStr;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+	nCommitStr = pgdata->chiSymbolBufLen;
+
+	if ( pgdata->bSelect || nCommitStr == 0 )
+		return -1;
+
+	WriteChiSymbolToBuf( pgo->commitStr, nCommitStr, pgdata );
+	AutoLearnPhrase( pgdata );
+	CleanAllBuf( pgdata );
+	pgdata->PointStart = -1;
+	pgdata->PointEnd = 0;
+	pgo->nCommitStr = nCommitStr;
+
+	MakeOutputWithRtn( pgo, pgdata, KEYSTROKE_COMMIT );
+	return 0;
+}
+
+CHEWING_API int chewing_clean_preedit_buf( ChewingContext *ctx )
+{
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
+	if ( pgdata->bSelect )
+		return -1;
+
+	CleanAllBuf( pgdata );
+	pgdata->PointStart = -1;
+	pgdata->PointEnd = 0;
+
+	MakeOutputWithRtn( pgo, pgdata, KEYSTROKE_ABSORB );
+	return 0;
+}
+
+CHEWING_API int chewing_clean_bopomofo_buf( ChewingContext *ctx )
+{
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
+	ZuinRemoveAll( &( pgdata->zuinData ) );
+
+	MakeOutputWithRtn( pgo, pgdata, KEYSTROKE_ABSORB );
+	return 0;
+}
+
+CHEWING_API int chewing_cand_open( ChewingContext *ctx )
+{
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
+	int key_buf_cursor;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
+	if ( pgdata->bSelect )
+		return 0;
+	if ( pgdata->chiSymbolBufLen == 0 )
+		return -1;
+
+	CheckAndResetRange( pgdata );
+
+	key_buf_cursor = pgdata->chiSymbolCursor;
+	if ( key_buf_cursor == pgdata->chiSymbolBufLen )
+		key_buf_cursor--;
+
+	chooseCandidate( ctx, ChewingIsChiAt( key_buf_cursor, pgdata ), key_buf_cursor );
+
+	MakeOutputWithRtn( pgo, pgdata, KEYSTROKE_ABSORB );
+	return pgdata->bSelect ? 0 : -1;
+}
+
+CHEWING_API int chewing_cand_close( ChewingContext *ctx )
+{
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
+	if ( pgdata->bSelect )
+		ChoiceEndChoice( pgdata );
+
+	MakeOutputWithRtn( pgo, pgdata, KEYSTROKE_ABSORB );
+	return 0;
+}
+
+CHEWING_API int chewing_cand_choose_by_index( ChewingContext *ctx, int index )
+{
+	ChewingData *pgdata;
+	ChewingOutput *pgo;
+
+	if ( !ctx )
+		return -1;
+	pgdata = ctx->data;
+	pgo = ctx->output;
+
+	if ( ! pgdata->bSelect || ! SelectCandidate( pgdata, index ) )
+		return -1;
+
+	CallPhrasing( pgdata );
+	MakeOutputWithRtn( pgo, pgdata, KEYSTROKE_ABSORB );
+	return 0;
+}
+
+static int SetCandList( ChewingContext *ctx, int avail )
+{
+	if ( ChoiceSetAvail( ctx->data, avail ) != 0 )
+		return -1;
+
+	MakeOutputWithRtn( ctx->output, ctx->data, KEYSTROKE_ABSORB );
+	return 0;
+}
+
+CHEWING_API int chewing_cand_list_first( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return SetCandList( ctx, ChoiceAvailOrder( ctx->data, 0 ) );
+}
+
+CHEWING_API int chewing_cand_list_last( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return SetCandList( ctx,
+		ChoiceAvailOrder( ctx->data, ctx->data->availInfo.nAvail - 1 ) );
+}
+
+CHEWING_API int chewing_cand_list_has_next( ChewingContext *ctx )
+{
+	if ( !ctx || ! ctx->data->bSelect || ctx->data->choiceInfo.isSymbol )
+		return 0;
+
+	return ChoiceAvailOrder( ctx->data, ctx->data->availInfo.currentAvail ) <
+		ctx->data->availInfo.nAvail - 1;
+}
+
+CHEWING_API int chewing_cand_list_has_prev( ChewingContext *ctx )
+{
+	if ( !ctx || ! ctx->data->bSelect || ctx->data->choiceInfo.isSymbol )
+		return 0;
+
+	return ChoiceAvailOrder( ctx->data, ctx->data->availInfo.currentAvail ) > 0;
+}
+
+CHEWING_API int chewing_cand_list_next( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return SetCandList( ctx, ChoiceAvailOrder( ctx->data,
+		ChoiceAvailOrder( ctx->data, ctx->data->availInfo.currentAvail ) + 1 ) );
+}
+
+CHEWING_API int chewing_cand_list_prev( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return SetCandList( ctx, ChoiceAvailOrder( ctx->data,
+		ChoiceAvailOrder( ctx->data, ctx->data->availInfo.currentAvail ) - 1 ) );
+}
+
 CHEWING_API unsigned short *chewing_get_phoneSeq( ChewingContext *ctx )
 {
 	uint16_t *seq;
+
+	if ( !ctx )
+		return NULL;
+
 	seq = ALC( uint16_t, ctx->data->nPhoneSeq );
 	memcpy( seq, ctx->data->phoneSeq, sizeof(uint16_t)*ctx->data->nPhoneSeq );
 	return seq;
@@ -1438,5 +2363,8 @@ CHEWING_API unsigned short *chewing_get_phoneSeq( ChewingContext *ctx )
 
 CHEWING_API int chewing_get_phoneSeqLen( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->data->nPhoneSeq;
 }
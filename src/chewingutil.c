@@ -31,6 +31,7 @@
 #include "choice-private.h"
 #include "tree-private.h"
 #include "userphrase-private.h"
+#include "key2pho-private.h"
 #include "private.h"
 
 #if HAVE_ASPRINTF
@@ -82,25 +83,21 @@ void SetUpdatePhraseMsg(
 		/* 加入： */
 	char *modify = "\xE5\xB7\xB2\xE6\x9C\x89\xEF\xBC\x9A";
 		/* 已有： */
+	char *remove = "\xE5\x88\xAA\xE9\x99\xA4\xEF\xBC\x9A";
+		/* 刪除： */
+	char *prefix;
 	int begin = 3, i;
 
 	pgdata->showMsgLen = begin + len;
-	if ( state == USER_UPDATE_INSERT ) {
-		ueStrNCpy( (char *) pgdata->showMsg[ 0 ].s, insert, 1, 1 );
-		ueStrNCpy( (char *) pgdata->showMsg[ 1 ].s,
-		           ueStrSeek( insert, 1 ), 
-		           1, 1 );
-		ueStrNCpy( (char *) pgdata->showMsg[ 2 ].s,
-		           ueStrSeek( insert, 2 ), 
-		           1, 1 );
-	}
-	else {
-		ueStrNCpy( (char *) pgdata->showMsg[ 0 ].s, modify, 1, 1 );
-		ueStrNCpy( (char *) pgdata->showMsg[ 1 ].s,
-		           ueStrSeek( modify, 1 ),
-			   1, 1 );
-		ueStrNCpy( (char *) pgdata->showMsg[ 2 ].s,
-		           ueStrSeek( modify, 2 ),
+	if ( state == USER_UPDATE_INSERT )
+		prefix = insert;
+	else if ( state == USER_UPDATE_REMOVE )
+		prefix = remove;
+	else
+		prefix = modify;
+	for ( i = 0; i < begin; i++ ) {
+		ueStrNCpy( (char *) pgdata->showMsg[ i ].s,
+		           ueStrSeek( prefix, i ),
 			   1, 1 );
 	}
 	for ( i = 0; i < len; i++ ) {
@@ -110,6 +107,28 @@ void SetUpdatePhraseMsg(
 	}
 }
 
+void SetCorrectPhoneMsg( ChewingData *pgdata, uint16_t phone )
+{
+	char *prefix = "\xE4\xBF\xAE\xE6\xAD\xA3\xEF\xBC\x9A";
+		/* 修正： */
+	char zuin[ ZUIN_SIZE * MAX_UTF8_SIZE + 1 ];
+	int begin = 3, len, i;
+
+	PhoneFromUint( zuin, sizeof( zuin ), phone );
+	len = ueStrLen( zuin );
+	pgdata->showMsgLen = begin + len;
+	for ( i = 0; i < begin; i++ ) {
+		ueStrNCpy( (char *) pgdata->showMsg[ i ].s,
+		           ueStrSeek( prefix, i ),
+			   1, 1 );
+	}
+	for ( i = 0; i < len; i++ ) {
+		ueStrNCpy( (char *) pgdata->showMsg[ begin + i ].s,
+		           ueStrSeek( zuin, i ),
+			   1, 1 );
+	}
+}
+
 int NoSymbolBetween( ChewingData *pgdata, int begin, int end )
 {
 	int i, nChi, k;
@@ -170,7 +189,7 @@ int HaninSymbolInput( ChewingData *pgdata )
 
 static int _Inner_InternalSpecialSymbol(
 		int key, ChewingData *pgdata, 
-		char symkey, char *chibuf )
+		char symkey, const char *chibuf )
 {
 	int kbtype;
 
@@ -205,7 +224,7 @@ static int _Inner_InternalSpecialSymbol(
 
 static int InternalSpecialSymbol(
 		int key, ChewingData *pgdata,
-		int nSpecial, const char keybuf[], char *chibuf[] )
+		int nSpecial, const char keybuf[], char * const chibuf[] )
 {
 	int i, rtn = ZUIN_IGNORE; /* very strange and difficult to understand */
 
@@ -220,14 +239,14 @@ static int InternalSpecialSymbol(
 
 int SpecialSymbolInput( int key, ChewingData *pgdata )
 {
-	static char keybuf[] = {
+	static const char keybuf[] = {
 		'[', ']', '{', '}', '\'','<', ':', '\"', '>',
 		'~', '!', '@', '#', '$', '%', '^', '&', '*',
 		'(', ')', '_', '+', '=','\\', '|', '?',
 		',', '.', ';'
 	};
 
-	static char *chibuf[] = {
+	static char * const chibuf[] = {
 		"\xE3\x80\x8C", "\xE3\x80\x8D", "\xE3\x80\x8E", "\xE3\x80\x8F",
 			/* "「", "」", "『", "』" */
 		"\xE3\x80\x81", "\xEF\xBC\x8C", "\xEF\xBC\x9A", "\xEF\xBC\x9B",
@@ -250,10 +269,36 @@ int SpecialSymbolInput( int key, ChewingData *pgdata )
 	return InternalSpecialSymbol( key, pgdata, ARRAY_SIZE( keybuf ), keybuf, chibuf );
 }
 
+/* insert the closing symbol after the cursor for an opening one */
+int SpecialSymbolPair( int key, ChewingData *pgdata )
+{
+	static const struct {
+		char key;
+		char closekey;
+		const char *closebuf;
+	} pair[] = {
+		{ '[', ']', "\xE3\x80\x8D" },	/* "「」" */
+		{ '{', '}', "\xE3\x80\x8F" },	/* "『』" */
+		{ '(', ')', "\xEF\xBC\x89" },	/* "（）" */
+	};
+	int i;
+
+	for ( i = 0; i < ARRAY_SIZE( pair ); i++ ) {
+		if ( key == pair[ i ].key ) {
+			_Inner_InternalSpecialSymbol( pair[ i ].closekey, pgdata,
+				pair[ i ].closekey, pair[ i ].closebuf );
+			/* keep the cursor between the pair */
+			pgdata->chiSymbolCursor--;
+			return 1;
+		}
+	}
+	return 0;
+}
+
 int FullShapeSymbolInput( int key, ChewingData *pgdata )
 {
 	int rtn;
-	static char keybuf[] = {
+	static const char keybuf[] = {
 		'0', '1', '2', '3',  '4',  '5', '6', '7', '8', '9',
 		'a', 'b', 'c', 'd',  'e',  'f', 'g', 'h', 'i', 'j',
 		'k', 'l', 'm', 'n',  'o',  'p', 'q', 'r', 's', 't',
@@ -263,7 +308,7 @@ int FullShapeSymbolInput( int key, ChewingData *pgdata )
 		'Y', 'Z', ' ', '\"', '\'', '/', '<', '>', '`', '[',
 		']', '{', '}', '+',  '-'
 	};
-	static char* chibuf[] = {
+	static char * const chibuf[] = {
 		"\xEF\xBC\x90","\xEF\xBC\x91","\xEF\xBC\x92","\xEF\xBC\x93",
 			/* "０","１","２","３" */
 		"\xEF\xBC\x94","\xEF\xBC\x95","\xEF\xBC\x96","\xEF\xBC\x97",
@@ -342,13 +387,13 @@ int EasySymbolInput( int key, ChewingData *pgdata )
 #if 0
 int SpecialEtenSymbolInput( int key, ChewingData *pgdata )
 {
-	static char keybuf[] = {
+	static const char keybuf[] = {
 		17, 23, 5, 18, 20, 25, 21, 9, 15, 16,
 		1, 19, 4, 6, 7, 8, 10, 11, 12, 59, 39,
 		26, 24, 3, 22, 2, 14, 13, 44, 46, 47
 	};
 
-	static char *chibuf[] = {
+	static char * const chibuf[] = {
 		"\xE2\x94\x8C","\xE2\x94\xAC","\xE2\x94\x90","\xE2\x96\xA1","\xE3\x80\x88",
 		"\xE3\x80\x89","\xE2\x80\xA6","\xE3\x80\x81","\xE3\x80\x82","\xE2\x80\xBB",
 		"\xE2\x94\x9C","\xE2\x94\xBC","\xE2\x94\xA4","\xE3\x80\x90","\xE3\x80\x91",
@@ -357,7 +402,7 @@ int SpecialEtenSymbolInput( int key, ChewingData *pgdata )
 		"\xE3\x80\x8A", "\xE3\x80\x8B" ,"\xE2\x94\x80", "\xEF\xBC\x8C","\xEF\xBC\x8E",
 		"\xEF\xBC\x9F"
 	};
-	static int nSpecial = 31;
+	int nSpecial = 31;
 	return InternalSpecialSymbol( key, pgdata, nSpecial, keybuf, chibuf );
 }
 #endif
@@ -568,12 +613,28 @@ void CleanAllBuf( ChewingData *pgdata )
 	pgdata->nPrefer = 0;
 }
 
+static void AutoLearnUpdatePhrase( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] )
+{
+	if ( pgdata->ext_config.autoLearn == AUTOLEARN_DISABLED )
+		return;
+	if ( UserIsPhraseSuppressed( pgdata, wordSeq ) )
+		return;
+	UserUpdatePhrase( pgdata, phoneSeq, wordSeq );
+}
+
 int ReleaseChiSymbolBuf( ChewingData *pgdata, ChewingOutput *pgo )
 {
 	int throwEnd;
 	uint16_t bufPhoneSeq[ MAX_PHONE_SEQ_LEN + 1 ];
 	char bufWordSeq[ MAX_PHONE_SEQ_LEN * MAX_UTF8_SIZE + 1 ];
 
+	/*
+	 * The candidate window refers to the positions in the buffer, so
+	 * the release is deferred until the selection is done.
+	 */
+	if ( pgdata->bSelect )
+		return 0;
+
 	throwEnd = CountReleaseNum( pgdata );
 
 	pgo->nCommitStr = throwEnd;
@@ -588,7 +649,7 @@ int ReleaseChiSymbolBuf( ChewingData *pgdata, ChewingOutput *pgo )
 		memcpy( bufPhoneSeq, pgdata->phoneSeq, sizeof( uint16_t ) * throwEnd );
 		bufPhoneSeq[ throwEnd ] = (uint16_t) 0;
 		ueStrNCpy( bufWordSeq, pgdata->phrOut.chiBuf, throwEnd, 1 );
-		UserUpdatePhrase( pgdata, bufPhoneSeq, bufWordSeq );
+		AutoLearnUpdatePhrase( pgdata, bufPhoneSeq, bufWordSeq );
 
 		KillFromLeft( pgdata, throwEnd );
 	}
@@ -597,7 +658,7 @@ int ReleaseChiSymbolBuf( ChewingData *pgdata, ChewingOutput *pgo )
 
 static int ChewingIsBreakPoint( int cursor, ChewingData *pgdata )
 {
-	static char *break_word[] = {
+	static const char * const break_word[] = {
 		"\xE6\x98\xAF", "\xE7\x9A\x84", "\xE4\xBA\x86", "\xE4\xB8\x8D",
 		/* 是              的              了              不 */
 		"\xE4\xB9\x9F", "\xE8\x80\x8C", "\xE4\xBD\xA0", "\xE6\x88\x91",
@@ -662,7 +723,7 @@ void AutoLearnPhrase( ChewingData *pgdata )
 		}
 		else {
 			if ( pending ) {
-				UserUpdatePhrase( pgdata, bufPhoneSeq, bufWordSeq );
+				AutoLearnUpdatePhrase( pgdata, bufPhoneSeq, bufWordSeq );
 				prev_pos = 0;
 				pending = 0;
 			}
@@ -671,11 +732,11 @@ void AutoLearnPhrase( ChewingData *pgdata )
 			ueStrNCpy( bufWordSeq,
 					ueStrSeek( (char *) &pgdata->phrOut.chiBuf, from ),
 					len, 1);
-			UserUpdatePhrase( pgdata, bufPhoneSeq, bufWordSeq );
+			AutoLearnUpdatePhrase( pgdata, bufPhoneSeq, bufWordSeq );
 		}
 	}
 	if ( pending ) {
-		UserUpdatePhrase( pgdata, bufPhoneSeq, bufWordSeq );
+		AutoLearnUpdatePhrase( pgdata, bufPhoneSeq, bufWordSeq );
 		prev_pos = 0;
 		pending = 0;
 	}
@@ -1120,7 +1181,11 @@ int ChewingKillChar(
 			(pgdata->nPhoneSeq - cursorToKill - 1) * sizeof( uint16_t ) );
 		pgdata->nPhoneSeq--;
 	}
-	pgdata->symbolKeyBuf[ chiSymbolCursorToKill ] = 0;
+	memmove(
+		& pgdata->symbolKeyBuf[ chiSymbolCursorToKill ],
+		& pgdata->symbolKeyBuf[ chiSymbolCursorToKill + 1 ],
+		(pgdata->chiSymbolBufLen - chiSymbolCursorToKill - 1) * sizeof( pgdata->symbolKeyBuf[ 0 ] ) );
+	pgdata->symbolKeyBuf[ pgdata->chiSymbolBufLen - 1 ] = 0;
 	memmove( 
 		& pgdata->chiSymbolBuf[ chiSymbolCursorToKill ],
 		& pgdata->chiSymbolBuf[ chiSymbolCursorToKill + 1 ], 
@@ -1145,7 +1210,7 @@ int IsPreferIntervalConnted( int cursor, ChewingData *pgdata )
 	return 0;
 }
 
-static char *symbol_buf[][ 50 ] = {
+static char * const symbol_buf[][ 50 ] = {
 	{ "0", "\xC3\xB8", 0 },
 		/* "ø" */
 	{ "[", "\xE3\x80\x8C", "\xE3\x80\x8E", "\xE3\x80\x8A", "\xE3\x80\x88",
@@ -1300,7 +1365,7 @@ static char *symbol_buf[][ 50 ] = {
 static int FindSymbolKey( const char *symbol )
 {
 	unsigned int i;
-	char **buf;
+	char * const *buf;
 	for ( i = 0; i < sizeof( symbol_buf ) / sizeof( symbol_buf[ 0 ] ); ++i ) {
 		for ( buf = symbol_buf[ i ]; *buf; ++buf )	{
 			if (  0 == strcmp( *buf, symbol ) )
@@ -1313,7 +1378,7 @@ static int FindSymbolKey( const char *symbol )
 int OpenSymbolChoice( ChewingData *pgdata )
 {
 	int i, symbol_buf_len = ARRAY_SIZE( symbol_buf );
-	char **pBuf;
+	char * const *pBuf;
 	ChoiceInfo *pci = &( pgdata->choiceInfo );
 	pci->oldChiSymbolCursor = pgdata->chiSymbolCursor;
 
@@ -91,6 +91,10 @@ static void SetAvailInfo( ChewingData *pgdata, int begin, int end)
 
 	pai->nAvail = 0;
 
+	/* no phone at the cursor, e.g. the cursor is on trailing symbols */
+	if ( end >= nPhoneSeq )
+		return;
+
 	if ( pgdata->config.bPhraseChoiceRearward ) {
 		for ( i = end; i >= begin; i--){
 			head = i;
@@ -162,7 +166,8 @@ static int ChoiceTheSame( ChoiceInfo *pci, char *str, int len )
 static void ChoiceInfoAppendChi( ChewingData *pgdata,  ChoiceInfo *pci, uint16_t phone )
 {
 	Word tempWord;
-	GetCharFirst( pgdata, &tempWord, phone );
+	if ( ! GetCharFirst( pgdata, &tempWord, phone ) )
+		return;
 	do {
 		if ( ChoiceTheSame( pci, tempWord.word,
 		                    ueBytesFromChar( tempWord.word[ 0 ] ) * sizeof( char ) ) )
@@ -177,6 +182,60 @@ static void ChoiceInfoAppendChi( ChewingData *pgdata,  ChoiceInfo *pci, uint16_t
 	} while ( GetCharNext( pgdata, &tempWord ) );
 }
 
+/* user frequency decayed linearly by the keystrokes since the last use */
+static long long RecentPhraseScore( ChewingData *pgdata, const UserPhraseData *pUserPhraseData )
+{
+	int age = pgdata->static_data.chewing_lifetime - pUserPhraseData->recentTime;
+
+	if ( age < 0 || age >= RECENT_PHRASE_INTERVAL )
+		return 0;
+	return (long long) ( pUserPhraseData->userfreq + 1 ) * ( RECENT_PHRASE_INTERVAL - age );
+}
+
+/** @brief Loading recently used user phrases, the highest score first. */
+static void ChoiceInfoAppendRecentUserPhrase(
+		ChewingData *pgdata,
+		ChoiceInfo *pci,
+		const uint16_t userPhoneSeq[],
+		int len )
+{
+	UserPhraseData *recent[ MAX_CHOICE ];
+	long long score[ MAX_CHOICE ];
+	UserPhraseData *pUserPhraseData;
+	long long s;
+	int nRecent = 0;
+	int i, j;
+
+	for ( pUserPhraseData = UserGetPhraseFirst( pgdata, userPhoneSeq );
+	      pUserPhraseData && nRecent < MAX_CHOICE;
+	      pUserPhraseData = UserGetPhraseNext( pgdata, userPhoneSeq ) ) {
+		s = RecentPhraseScore( pgdata, pUserPhraseData );
+		if ( s == 0 )
+			continue;
+		/* insertion sort, keep the enumeration order for equal scores */
+		for ( i = nRecent; i > 0 && score[ i - 1 ] < s; i-- ) {
+			recent[ i ] = recent[ i - 1 ];
+			score[ i ] = score[ i - 1 ];
+		}
+		recent[ i ] = pUserPhraseData;
+		score[ i ] = s;
+		nRecent++;
+	}
+
+	for ( j = 0; j < nRecent; j++ ) {
+		if ( ChoiceTheSame(
+			pci,
+			recent[ j ]->wordSeq,
+			len * ueBytesFromChar( recent[ j ]->wordSeq[0] ) * sizeof( char ) ) )
+			continue;
+		ueStrNCpy(
+				pci->totalChoiceStr[ pci->nTotalChoice ],
+				recent[ j ]->wordSeq,
+				len, 1);
+		pci->nTotalChoice++;
+	}
+}
+
 /** @brief Loading all possible phrases of certain length.
  *
  * Loading all possible phrases of certain length into ChoiceInfo structure
@@ -201,6 +260,7 @@ static void SetChoiceInfo( ChewingData *pgdata )
 		sizeof(char) * MAX_CHOICE * MAX_PHRASE_LEN * MAX_UTF8_SIZE + 1);
 
 	pci->nTotalChoice = 0;
+	pci->bRemoveUserPhrase = 0;
 	len = pai->avail[ pai->currentAvail ].len;
 	assert(len);
 
@@ -277,6 +337,12 @@ static void SetChoiceInfo( ChewingData *pgdata )
 	}
 	/* phrase */
 	else {
+		memcpy( userPhoneSeq, &phoneSeq[ cursor ], sizeof( uint16_t ) * len );
+		userPhoneSeq[ len ] = 0;
+
+		if ( pgdata->ext_config.bRecentPhraseFirst )
+			ChoiceInfoAppendRecentUserPhrase( pgdata, pci, userPhoneSeq, len );
+
 		if ( pai->avail[ pai->currentAvail ].id != -1 ) {
 			GetPhraseFirst( pgdata, &tempPhrase, pai->avail[ pai->currentAvail ].id );
 			do {
@@ -292,8 +358,6 @@ static void SetChoiceInfo( ChewingData *pgdata )
 			} while( GetPhraseNext( pgdata, &tempPhrase ) );
 		}
 
-		memcpy( userPhoneSeq, &phoneSeq[ cursor ], sizeof( uint16_t ) * len );
-		userPhoneSeq[ len ] = 0;
 		pUserPhraseData = UserGetPhraseFirst( pgdata, userPhoneSeq );
 		if ( pUserPhraseData ) {
 			do {
@@ -345,6 +409,9 @@ int ChoiceFirstAvail( ChewingData *pgdata )
 	/* save old cursor position */
 	pgdata->choiceInfo.oldChiSymbolCursor = pgdata->chiSymbolCursor;
 
+	/* the candidates are phrases even if symbols were being chosen */
+	pgdata->choiceInfo.isSymbol = 0;
+
 	/* see if there is some word in the cursor position */
 	if ( pgdata->chiSymbolBufLen == pgdata->chiSymbolCursor ) {
 		pgdata->chiSymbolCursor--;
@@ -365,6 +432,9 @@ int ChoiceFirstAvail( ChewingData *pgdata )
 	if ( ! pgdata->availInfo.nAvail )
 		return ChoiceEndChoice( pgdata );
 
+	if ( pgdata->ext_config.bShortestPhraseFirst )
+		return ChoiceSetAvail( pgdata, ChoiceAvailOrder( pgdata, 0 ) );
+
 	pgdata->availInfo.currentAvail = pgdata->availInfo.nAvail - 1;
 	SetChoiceInfo( pgdata );
 	return 0;
@@ -380,12 +450,34 @@ int ChoicePrevAvail( ChewingContext *ctx )
 	return 0;
 }
 
-/** @brief Return the next phrase not longer than the previous phrase. */
-int ChoiceNextAvail( ChewingData *pgdata ) 
+/**
+ * @brief Map between the position in the listing order and the index in
+ * AvailInfo, which is sorted from short to long. The mapping is its own
+ * inverse.
+ */
+int ChoiceAvailOrder( ChewingData *pgdata, int n )
 {
-	if (pgdata->choiceInfo.isSymbol) return 0;
-	if ( --( pgdata->availInfo.currentAvail ) < 0 )
-		pgdata->availInfo.currentAvail = pgdata->availInfo.nAvail - 1;
+	if ( pgdata->ext_config.bShortestPhraseFirst )
+		return n;
+	return pgdata->availInfo.nAvail - 1 - n;
+}
+
+/** @brief Show the phrases of the given available length. */
+int ChoiceSetAvail( ChewingData *pgdata, int avail )
+{
+	if ( ! pgdata->bSelect || pgdata->choiceInfo.isSymbol )
+		return -1;
+	if ( avail < 0 || avail >= pgdata->availInfo.nAvail )
+		return -1;
+
+	/* the phrase ends at the cursor, so move the cursor to its head */
+	if ( pgdata->config.bPhraseChoiceRearward ) {
+		pgdata->chiSymbolCursor = pgdata->choiceInfo.oldChiSymbolCursor -
+			pgdata->availInfo.avail[ avail ].len;
+		if ( pgdata->chiSymbolBufLen > pgdata->choiceInfo.oldChiSymbolCursor )
+			pgdata->chiSymbolCursor++;
+	}
+	pgdata->availInfo.currentAvail = avail;
 	SetChoiceInfo( pgdata );
 	return 0;
 }
@@ -401,6 +493,7 @@ int ChoiceEndChoice( ChewingData *pgdata )
 		pgdata->chiSymbolCursor = pgdata->choiceInfo.oldChiSymbolCursor;
 	}
 	pgdata->choiceInfo.isSymbol = 0;
+	pgdata->choiceInfo.bRemoveUserPhrase = 0;
 	return 0;
 }
 
@@ -434,3 +527,35 @@ int ChoiceSelect( ChewingData *pgdata, int selectNo )
 	return 0;
 }
 
+/** @brief remove the selected phrase from the user phrase database. */
+int ChoiceRemoveUserPhrase( ChewingData *pgdata, int selectNo )
+{
+	ChoiceInfo *pci = &( pgdata->choiceInfo );
+	uint16_t userPhoneSeq[ MAX_PHONE_SEQ_LEN ];
+	int len;
+
+	pci->bRemoveUserPhrase = 0;
+	if ( pci->isSymbol || selectNo < 0 || selectNo >= pci->nTotalChoice )
+		return 0;
+
+	len = ueStrLen( pci->totalChoiceStr[ selectNo ] );
+	memcpy(
+		userPhoneSeq,
+		&( pgdata->phoneSeq[ PhoneSeqCursor( pgdata ) ] ),
+		len * sizeof( uint16_t ) );
+	userPhoneSeq[ len ] = 0;
+	if ( ! UserRemovePhrase( pgdata, userPhoneSeq, pci->totalChoiceStr[ selectNo ] ) )
+		return 0;
+
+	SetUpdatePhraseMsg(
+		pgdata,
+		pci->totalChoiceStr[ selectNo ],
+		len,
+		USER_UPDATE_REMOVE );
+
+	/* reload the candidates since the removed phrase might be gone */
+	SetChoiceInfo( pgdata );
+	if ( pci->nTotalChoice == 0 )
+		ChoiceEndChoice( pgdata );
+	return 1;
+}
@@ -86,6 +86,18 @@ static const char *key_str[ MAX_KBTYPE ] = {
 	 "1qaz2wsxedcrfv5tgbyhnujm8ik,9ol.0p;/-7634",		/* pinyin */
 } ;
 
+/*
+ * Layout of the phone number (uint16_t), from the least significant bit:
+ *
+ *   bit  0 -  2  tone    0: none or first tone, 1: ˙, 2: ˊ, 3: ˇ, 4: ˋ
+ *   bit  3 -  6  final   1: ㄚ ... 13: ㄦ
+ *   bit  7 -  8  medial  1: ㄧ, 2: ㄨ, 3: ㄩ
+ *   bit  9 - 13  initial 1: ㄅ ... 21: ㄙ
+ *
+ * A value of 0 in a field means the zhuin is absent. The layout is stored
+ * as-is in the dictionary and in the user hash file, so it must not change.
+ */
+
 /* 
  * Read one zhuin string,
  *
@@ -146,24 +158,44 @@ int PhoneFromKey( char *pho, const char *inputkey, int kbtype, int searchTimes )
 	return 1;
 }
 
-#if 0
-int PhoneFromUint( char *phone, long seq )
+/*
+ * Convert the phone number back to its zhuin string.
+ *
+ * return the number of zhuins written to 'phone', or 0 if 'phone_num'
+ * is not a valid phone number.
+ */
+int PhoneFromUint( char *phone, size_t phone_len, uint16_t phone_num )
 {
-    int i, j, k;
-    char *pos;
-    char buffer[7];
-    for ( i = 0, j = 0; i < 4; i++) {
-        k = ((seq >> shift[ i ]) & sb[ i ] ) - 1;
-        if ( k >= 0 && (pos = ueStrSeek( (char*)zhuin_tab[ i ], k )) )
-        {
-			ueStrNCpy(buffer, pos, 1, 1);
-            strcat(phone, buffer);
-            j++;
-        }
-    }
-    return j;
+	int i, index, count = 0;
+	const char *pos;
+	size_t len = 0, n;
+
+	if ( phone_len == 0 )
+		return 0;
+	phone[ 0 ] = '\0';
+	/* Only the lowest 14 bits carry zhuin, see shift[] and sb[] */
+	if ( phone_num >> 14 )
+		return 0;
+	/* Here the constant 4 is the number
+	   of zhuin_tab and zhuin_tab_num */
+	for ( i = 0; i < 4; i++ ) {
+		index = ( phone_num >> shift[ i ] ) & sb[ i ];
+		if ( index == 0 )
+			continue;
+		if ( index >= zhuin_tab_num[ i ] )
+			return 0;
+		/* zhuin_tab[ i ] starts with two spaces */
+		pos = ueStrSeek( (char *) zhuin_tab[ i ], index + 1 );
+		n = ueBytesFromChar( pos[ 0 ] );
+		if ( len + n >= phone_len )
+			return 0;
+		memcpy( phone + len, pos, n );
+		len += n;
+		phone[ len ] = '\0';
+		++count;
+	}
+	return count;
 }
-#endif
 
 int PhoneInxFromKey( int key, int type, int kbtype, int searchTimes )
 {
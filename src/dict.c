@@ -155,6 +155,8 @@ int GetPhraseFirst( ChewingData *pgdata, Phrase *phr_ptr, int phone_phr_id )
 {
 	assert( ( 0 <= phone_phr_id ) && ( phone_phr_id < PHONE_PHRASE_NUM ) );
 
+	LOG_VERBOSE( "phone_phr_id = %d", phone_phr_id );
+
 #ifndef USE_BINARY_DATA
 	fseek( pgdata->static_data.dictfile, pgdata->static_data.dict_begin[ phone_phr_id ], SEEK_SET );
 #else
@@ -78,6 +78,26 @@ HASH_ITEM *HashFindPhonePhrase( ChewingData *pgdata, const uint16_t phoneSeq[],
 	return NULL;
 }
 
+/*
+ * Return the item after pItem in the hash table, or the first item if pItem
+ * is NULL.
+ */
+HASH_ITEM *HashNext( ChewingData *pgdata, HASH_ITEM *pItem )
+{
+	int i = 0;
+
+	if ( pItem ) {
+		if ( pItem->next )
+			return pItem->next;
+		i = HashFunc( pItem->data.phoneSeq ) + 1;
+	}
+	for ( ; i < HASH_TABLE_SIZE; i++ ) {
+		if ( pgdata->static_data.hashtable[ i ] )
+			return pgdata->static_data.hashtable[ i ];
+	}
+	return NULL;
+}
+
 HASH_ITEM *HashFindEntry( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] )
 {
 	HASH_ITEM *pItem;
@@ -117,6 +137,7 @@ HASH_ITEM *HashInsert( ChewingData *pgdata, UserPhraseData *pData )
 
 	/* set link to the new element */
 	pgdata->static_data.hashtable[ hashvalue ] = pItem;
+	++pgdata->static_data.n_hash_item;
 
 	return pItem;
 }
@@ -141,13 +162,33 @@ static void HashItem2String( char *str, HASH_ITEM *pItem )
 }
 #endif
 
+/*
+ * Phone numbers are stored in little-endian byte order.
+ */
+static void PutPhoneSeq( unsigned char *buf, const uint16_t phoneSeq[], int len )
+{
+	int i;
+
+	for ( i = 0; i < len; i++ ) {
+		buf[ i * 2 ] = phoneSeq[ i ] & 0xff;
+		buf[ i * 2 + 1 ] = phoneSeq[ i ] >> 8;
+	}
+}
+
+static void GetPhoneSeq( uint16_t phoneSeq[], const unsigned char *buf, int len )
+{
+	int i;
+
+	for ( i = 0; i < len; i++ )
+		phoneSeq[ i ] = buf[ i * 2 ] | ( buf[ i * 2 + 1 ] << 8 );
+}
+
 /* 
  * capacity of 'str' MUST bigger then FIELD_SIZE !
  */
 void HashItem2Binary( char *str, HASH_ITEM *pItem )
 {
-	int i, phraselen;
-	uint16_t *pshort;
+	int phraselen;
 	unsigned char *puc;
 
 	memset( str, 0, FIELD_SIZE );
@@ -166,23 +207,56 @@ void HashItem2Binary( char *str, HASH_ITEM *pItem )
 	/* phone seq*/
 	phraselen = ueStrLen( pItem->data.wordSeq );
 	str[ 16 ] = phraselen;
-	pshort = (uint16_t *) &str[ 17 ];
-	for ( i = 0; i < phraselen; i++ ) {
-		*pshort = pItem->data.phoneSeq[ i ];
-		pshort++;
-	}
+	PutPhoneSeq( (unsigned char *) &str[ 17 ], pItem->data.phoneSeq, phraselen );
 
 	/* phrase */
-	puc = (unsigned char *) pshort;
+	puc = (unsigned char *) &str[ 17 + phraselen * 2 ];
 	*puc = strlen( pItem->data.wordSeq );
 	strcpy( (char *) (puc + 1), pItem->data.wordSeq );
 	pItem->data.wordSeq[ (int) *puc ] = '\0';
 }
 
+/* remember a cleared record so that HashModify() can reuse it */
+static void AddFreeSlot( ChewingData *pgdata, int item_index )
+{
+	int *slot;
+
+	slot = realloc( pgdata->static_data.hash_free_slot,
+		sizeof( int ) * ( pgdata->static_data.n_hash_free_slot + 1 ) );
+	if ( ! slot )
+		return;
+	slot[ pgdata->static_data.n_hash_free_slot++ ] = item_index;
+	pgdata->static_data.hash_free_slot = slot;
+}
+
+/*
+ * Another context sharing the hash file might have reused a cleared record.
+ * Check that the record at item_index still holds the phrase of str, or is
+ * still cleared if str is NULL. The freq info is not compared since other
+ * contexts update it.
+ */
+static int IsRecordOwned( FILE *file, int item_index, const char *str )
+{
+	char buf[ FIELD_SIZE ];
+	int i;
+
+	fseek( file, item_index * FIELD_SIZE + 4 + strlen( BIN_HASH_SIG ), SEEK_SET );
+	if ( fread( buf, 1, FIELD_SIZE, file ) != FIELD_SIZE )
+		return 0;
+	if ( str )
+		return memcmp( buf + 16, str + 16, FIELD_SIZE - 16 ) == 0;
+	for ( i = 0; i < FIELD_SIZE; ++i ) {
+		if ( buf[ i ] )
+			return 0;
+	}
+	return 1;
+}
+
 void HashModify( ChewingData *pgdata, HASH_ITEM *pItem )
 {
 	FILE *outfile;
 	char str[ FIELD_SIZE + 1 ];
+	int item_index;
 
 	outfile = fopen( pgdata->static_data.hashfilename, "r+b" );
 
@@ -195,6 +269,20 @@ void HashModify( ChewingData *pgdata, HASH_ITEM *pItem )
 	DEBUG_FLUSH;
 #endif
 
+	HashItem2Binary( str, pItem );
+
+	/* the record was taken by another context, so append a new one */
+	if ( pItem->item_index >= 0 && ! IsRecordOwned( outfile, pItem->item_index, str ) )
+		pItem->item_index = -1;
+
+	/* reuse a cleared record before growing the file */
+	while ( pItem->item_index < 0 && pgdata->static_data.n_hash_free_slot > 0 ) {
+		item_index = pgdata->static_data.hash_free_slot[
+			--pgdata->static_data.n_hash_free_slot ];
+		if ( IsRecordOwned( outfile, item_index, NULL ) )
+			pItem->item_index = item_index;
+	}
+
 	/* update record */
 	if ( pItem->item_index < 0 ) {
 		fseek( outfile, 0, SEEK_END );
@@ -210,14 +298,66 @@ void HashModify( ChewingData *pgdata, HASH_ITEM *pItem )
 	HashItem2String( str, pItem );
 	DEBUG_OUT( "HashModify-2: '%-75s'\n", str );
 	DEBUG_FLUSH;
-#endif
 	HashItem2Binary( str, pItem );
+#endif
 	fwrite( str, 1, FIELD_SIZE, outfile );
 	fflush( outfile );
 	fclose( outfile );
 }
 
-static int isValidChineseString( char *str )
+/*
+ * Remove the entry from the hash table. The record in the hash file is
+ * cleared so that it is ignored in the next InitHash().
+ */
+int HashRemove( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] )
+{
+	HASH_ITEM **ppItem, *pItem;
+	FILE *outfile;
+	char str[ FIELD_SIZE ];
+
+	for ( ppItem = &pgdata->static_data.hashtable[ HashFunc( phoneSeq ) ];
+	      *ppItem; ppItem = &( *ppItem )->next ) {
+		pItem = *ppItem;
+		if (
+			! strcmp( pItem->data.wordSeq, wordSeq ) &&
+			PhoneSeqTheSame( pItem->data.phoneSeq, phoneSeq ) ) {
+			break;
+		}
+	}
+	if ( ! *ppItem )
+		return 0;
+
+	pItem = *ppItem;
+	*ppItem = pItem->next;
+	--pgdata->static_data.n_hash_item;
+
+	if ( pItem->item_index >= 0 ) {
+		outfile = fopen( pgdata->static_data.hashfilename, "r+b" );
+		HashItem2Binary( str, pItem );
+		/* do not clear a record reused by another context */
+		if ( outfile && ! IsRecordOwned( outfile, pItem->item_index, str ) ) {
+			fclose( outfile );
+			outfile = NULL;
+		}
+		if ( outfile ) {
+			memset( str, 0, FIELD_SIZE );
+			fseek( outfile,
+				pItem->item_index * FIELD_SIZE + 4 + strlen( BIN_HASH_SIG ),
+				SEEK_SET );
+			fwrite( str, 1, FIELD_SIZE, outfile );
+			fflush( outfile );
+			fclose( outfile );
+			AddFreeSlot( pgdata, pItem->item_index );
+		}
+	}
+
+	free( pItem->data.phoneSeq );
+	free( pItem->data.wordSeq );
+	free( pItem );
+	return 1;
+}
+
+int isValidChineseString( const char *str )
 {
 	if ( str == NULL || *str == '\0' ) {
 		return 0;
@@ -247,8 +387,7 @@ static int ReadInt(unsigned char *addr)
  */
 int ReadHashItem_bin( const char *srcbuf, HASH_ITEM *pItem, int item_index )
 {
-	int len, i;
-	uint16_t *pshort;
+	int len;
 	unsigned char recbuf[ FIELD_SIZE ], *puc;
 
 	memcpy( recbuf, srcbuf, FIELD_SIZE );
@@ -263,15 +402,11 @@ int ReadHashItem_bin( const char *srcbuf, HASH_ITEM *pItem, int item_index )
 	/* phone seq, length in num of chi words */
 	len = (int) recbuf[ 16 ];
 	pItem->data.phoneSeq = ALC( uint16_t, len + 1 );
-	pshort = (uint16_t *) &recbuf[ 17 ];
-	for ( i = 0; i < len; i++ ) {
-		pItem->data.phoneSeq[ i ] = *pshort;
-		++pshort;
-	}
-	pItem->data.phoneSeq[ i ] = 0;
+	GetPhoneSeq( pItem->data.phoneSeq, &recbuf[ 17 ], len );
+	pItem->data.phoneSeq[ len ] = 0;
 
 	/* phrase, length in num of bytes */
-	puc = (unsigned char *) pshort;
+	puc = &recbuf[ 17 + len * 2 ];
 	pItem->data.wordSeq = ALC( char, (*puc) + 1 );
 	strcpy( pItem->data.wordSeq, (char *) (puc + 1) );
 	pItem->data.wordSeq[ (int) *puc ] = '\0';
@@ -520,35 +655,67 @@ void TerminateHash( ChewingData *pgdata )
 		DEBUG_CHECKPOINT();
 		FreeHashItem( pItem );
 	}
+	pgdata->static_data.n_hash_item = 0;
+	free( pgdata->static_data.hash_free_slot );
+	pgdata->static_data.hash_free_slot = NULL;
+	pgdata->static_data.n_hash_free_slot = 0;
 }
 
-int InitHash( ChewingData *pgdata )
+/* Set hashfilename to the file in dir. Return 0 if it does not fit. */
+static int SetHashFileName( ChewingData *pgdata, const char *dir )
+{
+	int len;
+
+	len = snprintf( pgdata->static_data.hashfilename,
+		sizeof( pgdata->static_data.hashfilename ),
+		"%s" PLAT_SEPARATOR "%s", dir, HASH_FILE );
+	if ( len < 0 || (size_t) len >= sizeof( pgdata->static_data.hashfilename ) ) {
+		LOG_ERROR( "User path %s is too long", dir );
+		pgdata->static_data.hashfilename[ 0 ] = '\0';
+		return 0;
+	}
+	return 1;
+}
+
+int InitHash( ChewingData *pgdata, const char *path )
 {
 	HASH_ITEM item, *pItem, *pPool = NULL;
 	int item_index, hashvalue, iret, fsize, hdrlen, oldest = INT_MAX;
 	char *dump, *seekdump;
+	char dir[ sizeof( pgdata->static_data.hashfilename ) ];
+	int len;
 
-	const char *path = getenv( "CHEWING_USER_PATH" );
+	memset( pgdata->static_data.hashtable, 0, sizeof( pgdata->static_data.hashtable ) );
+	pgdata->static_data.n_hash_item = 0;
 
-	/* make sure of write permission */
-	if ( path && access( path, W_OK ) == 0 ) {
-		sprintf( pgdata->static_data.hashfilename, "%s" PLAT_SEPARATOR "%s", path, HASH_FILE );
-	} else {
-		if ( getenv( "HOME" ) ) {
-			sprintf(
-				pgdata->static_data.hashfilename, "%s%s",
-				getenv( "HOME" ), CHEWING_HASH_PATH );
+	if ( path ) {
+		/* never use another database than the one given by the caller */
+		if ( access( path, W_OK ) != 0 ) {
+			LOG_ERROR( "Cannot write to user path %s", path );
+			return 0;
 		}
-		else {
-			sprintf(
-				pgdata->static_data.hashfilename, "%s%s",
-				PLAT_TMPDIR, CHEWING_HASH_PATH );
+		if ( ! SetHashFileName( pgdata, path ) )
+			return 0;
+	} else {
+		path = getenv( "CHEWING_USER_PATH" );
+
+		/* make sure of write permission */
+		if ( path && access( path, W_OK ) == 0 ) {
+			if ( ! SetHashFileName( pgdata, path ) )
+				return 0;
+		} else {
+			len = snprintf( dir, sizeof( dir ), "%s%s",
+				getenv( "HOME" ) ? getenv( "HOME" ) : PLAT_TMPDIR,
+				CHEWING_HASH_PATH );
+			if ( len < 0 || (size_t) len >= sizeof( dir ) ) {
+				LOG_ERROR( "User path %s is too long", dir );
+				return 0;
+			}
+			PLAT_MKDIR( dir );
+			if ( ! SetHashFileName( pgdata, dir ) )
+				return 0;
 		}
-		PLAT_MKDIR( pgdata->static_data.hashfilename );
-		strcat( pgdata->static_data.hashfilename, PLAT_SEPARATOR );
-		strcat( pgdata->static_data.hashfilename, HASH_FILE );
 	}
-	memset( pgdata->static_data.hashtable, 0, sizeof( pgdata->static_data.hashtable ) );
 
 open_hash_file:
 	dump = _load_hash_file( pgdata->static_data.hashfilename, &fsize );
@@ -558,6 +725,7 @@ open_hash_file:
 		FILE *outfile;
 		outfile = fopen( pgdata->static_data.hashfilename, "w+b" );
 		if ( ! outfile ) {
+			LOG_ERROR( "Cannot open %s", pgdata->static_data.hashfilename );
 			if ( dump ) {
 				free( dump );
 			}
@@ -572,6 +740,7 @@ open_hash_file:
 	else {
 		if ( memcmp(dump, BIN_HASH_SIG, strlen(BIN_HASH_SIG)) != 0 ) {
 			/* perform migrate from text-based to binary form */
+			LOG_INFO( "Migrate %s to binary form", pgdata->static_data.hashfilename );
 			free( dump );
 			if ( ! migrate_hash_to_bin( pgdata, pgdata->static_data.hashfilename ) ) {
 				return  0;
@@ -585,11 +754,15 @@ open_hash_file:
 
 		while ( fsize >= FIELD_SIZE ) {
 			iret = ReadHashItem_bin( seekdump, &item, item_index++ );
-			/* Ignore illegal data */
+			/*
+			 * Ignore illegal data. Its slot in the file is
+			 * reused by the next new phrase.
+			 */
 			if ( iret == -1 ) {
+				LOG_WARN( "Ignore illegal hash item %d", item_index - 1 );
+				AddFreeSlot( pgdata, item_index - 1 );
 				seekdump += FIELD_SIZE;
 				fsize -= FIELD_SIZE;
-				--item_index;
 				continue;
 			}
 			else if ( iret == 0 )
@@ -616,6 +789,7 @@ open_hash_file:
 			hashvalue = HashFunc( pItem->data.phoneSeq );
 			pItem->next = pgdata->static_data.hashtable[ hashvalue ];
 			pgdata->static_data.hashtable[ hashvalue ] = pItem;
+			++pgdata->static_data.n_hash_item;
 			pItem->data.recentTime -= oldest;
 		}
 		pgdata->static_data.chewing_lifetime -= oldest;
@@ -20,6 +20,7 @@
 #include "chewing-private.h"
 #include "zuin-private.h"
 #include "chewingio.h"
+#include "chewingutil.h"
 #include "private.h"
 
 /**
@@ -28,6 +29,9 @@
  */
 CHEWING_API int chewing_commit_Check( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return !!(ctx->output->keystrokeRtn & KEYSTROKE_COMMIT);
 }
 
@@ -40,7 +44,12 @@ CHEWING_API int chewing_commit_Check( ChewingContext *ctx )
 CHEWING_API char *chewing_commit_String( ChewingContext *ctx )
 {
 	int i;
-	char *s = (char *) calloc(
+	char *s;
+
+	if ( !ctx )
+		return NULL;
+
+	s = (char *) calloc(
 		1 + ctx->output->nCommitStr,
 		sizeof(char) * MAX_UTF8_SIZE );
 	for ( i = 0; i < ctx->output->nCommitStr; i++ ) {
@@ -49,20 +58,45 @@ CHEWING_API char *chewing_commit_String( ChewingContext *ctx )
 	return s;
 }
 
+CHEWING_API const char *chewing_commit_String_static( ChewingContext *ctx )
+{
+	int i;
+
+	if ( !ctx )
+		return NULL;
+
+	ctx->commit_buf[ 0 ] = '\0';
+	for ( i = 0; i < ctx->output->nCommitStr; i++ ) {
+		strcat( ctx->commit_buf, (char *) (ctx->output->commitStr[ i ].s) );
+	}
+	return ctx->commit_buf;
+}
+
 CHEWING_API int chewing_buffer_Check( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (ctx->output->chiSymbolBufLen != 0);
 }
 
 CHEWING_API int chewing_buffer_Len( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return ctx->output->chiSymbolBufLen;
 }
 
 CHEWING_API char *chewing_buffer_String( ChewingContext *ctx )
 {
 	int i;
-	char *s = (char *) calloc(
+	char *s;
+
+	if ( !ctx )
+		return NULL;
+
+	s = (char *) calloc(
 		1 + ctx->output->chiSymbolBufLen,
 		sizeof(char) * MAX_UTF8_SIZE );
 	for ( i = 0; i < ctx->output->chiSymbolBufLen; i++ ) {
@@ -71,6 +105,20 @@ CHEWING_API char *chewing_buffer_String( ChewingContext *ctx )
 	return s;
 }
 
+CHEWING_API const char *chewing_buffer_String_static( ChewingContext *ctx )
+{
+	int i;
+
+	if ( !ctx )
+		return NULL;
+
+	ctx->preedit_buf[ 0 ] = '\0';
+	for ( i = 0; i < ctx->output->chiSymbolBufLen; i++ ) {
+		strcat( ctx->preedit_buf, (char *) (ctx->output->chiSymbolBuf[ i ].s) );
+	}
+	return ctx->preedit_buf;
+}
+
 /**
  * @param ctx handle to Chewing IM context
  * @param zuin_count pointer to the integer of available Zuin preedit string
@@ -81,6 +129,10 @@ CHEWING_API char *chewing_zuin_String( ChewingContext *ctx, int *zuin_count )
 {
 	char *s;
 	int i;
+
+	if ( !ctx )
+		return NULL;
+
 	if ( zuin_count )
 		*zuin_count = 0;
 	s = (char*) calloc(
@@ -99,55 +151,111 @@ CHEWING_API char *chewing_zuin_String( ChewingContext *ctx, int *zuin_count )
 CHEWING_API int chewing_zuin_Check( ChewingContext *ctx )
 {
 	int ret = 0;
+
+	if ( !ctx )
+		return -1;
+
 	if ( ctx->output->zuinBuf[ 0 ].s[ 0 ] == '\0' ) {
 		ret = 1;
 	}
 	return ret;
 }
 
+CHEWING_API int chewing_bopomofo_Check( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return ctx->output->zuinBuf[ 0 ].s[ 0 ] != '\0';
+}
+
+CHEWING_API const char *chewing_bopomofo_String_static( ChewingContext *ctx )
+{
+	int i;
+
+	if ( !ctx )
+		return NULL;
+
+	ctx->bopomofo_buf[ 0 ] = '\0';
+	for ( i = 0; i < ZUIN_SIZE; i++ ) {
+		strcat( ctx->bopomofo_buf, (char *) (ctx->output->zuinBuf[ i ].s) );
+	}
+	return ctx->bopomofo_buf;
+}
+
 CHEWING_API int chewing_cursor_Current( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (ctx->output->chiSymbolCursor);
 }
 
 CHEWING_API int chewing_cand_CheckDone( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (! ctx->output->pci);
 }
 
 CHEWING_API int chewing_cand_TotalPage( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (ctx->output->pci ? ctx->output->pci->nPage : 0);
 }
 
 CHEWING_API int chewing_cand_ChoicePerPage( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (ctx->output->pci ? ctx->output->pci->nChoicePerPage : 0);
 }
 
 CHEWING_API int chewing_cand_TotalChoice( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (ctx->output->pci ? ctx->output->pci->nTotalChoice : 0);
 }
 
 CHEWING_API int chewing_cand_CurrentPage( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (ctx->output->pci ? ctx->output->pci->pageNo : -1);
 }
 
 CHEWING_API void chewing_cand_Enumerate( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return;
+
+	if ( !ctx->output->pci )
+		return;
 	ctx->cand_no = ctx->output->pci->pageNo * ctx->output->pci->nChoicePerPage;
 }
 
 CHEWING_API int chewing_cand_hasNext( ChewingContext *ctx )
 {
-	return (ctx->cand_no < ctx->output->pci->nTotalChoice);	
+	if ( !ctx || !ctx->output->pci )
+		return 0;
+
+	return (ctx->cand_no < ctx->output->pci->nTotalChoice);
 }
 
 CHEWING_API char *chewing_cand_String( ChewingContext *ctx )
 {
 	char *s;
+
+	if ( !ctx )
+		return NULL;
+
 	if ( chewing_cand_hasNext( ctx ) ) {
 		s = strdup( ctx->output->pci->totalChoiceStr[ ctx->cand_no ] );
 		ctx->cand_no++;
@@ -157,18 +265,41 @@ CHEWING_API char *chewing_cand_String( ChewingContext *ctx )
 	return s;
 }
 
+CHEWING_API const char *chewing_cand_String_static( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return NULL;
+
+	if ( chewing_cand_hasNext( ctx ) ) {
+		strcpy( ctx->cand_buf, ctx->output->pci->totalChoiceStr[ ctx->cand_no ] );
+		ctx->cand_no++;
+	} else {
+		ctx->cand_buf[ 0 ] = '\0';
+	}
+	return ctx->cand_buf;
+}
+
 CHEWING_API void chewing_interval_Enumerate( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return;
+
 	ctx->it_no = 0;
 }
 
 CHEWING_API int chewing_interval_hasNext( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return 0;
+
 	return (ctx->it_no < ctx->output->nDispInterval);
 }
 
 CHEWING_API void chewing_interval_Get( ChewingContext *ctx, IntervalType *it )
 {
+	if ( !ctx )
+		return;
+
 	if ( chewing_interval_hasNext( ctx ) ) {
 		if ( it ) {
 			it->from = ctx->output->dispInterval[ ctx->it_no ].from;
@@ -178,20 +309,103 @@ CHEWING_API void chewing_interval_Get( ChewingContext *ctx, IntervalType *it )
 	}
 }
 
+/**
+ * @param ctx handle to Chewing IM context
+ * @param char_offset character offset in the preedit buffer
+ *
+ * Returns the byte offset in the UTF-8 preedit string, or -1 if char_offset
+ * is out of range.
+ */
+CHEWING_API int chewing_buffer_CharToByte( ChewingContext *ctx, int char_offset )
+{
+	int i, byte_offset = 0;
+
+	if ( !ctx )
+		return -1;
+
+	if ( char_offset < 0 || char_offset > ctx->output->chiSymbolBufLen )
+		return -1;
+	for ( i = 0; i < char_offset; i++ )
+		byte_offset += strlen( (char *) ctx->output->chiSymbolBuf[ i ].s );
+	return byte_offset;
+}
+
+/**
+ * @param ctx handle to Chewing IM context
+ * @param byte_offset byte offset in the UTF-8 preedit string
+ *
+ * Returns the character offset in the preedit buffer, or -1 if byte_offset
+ * is out of range or not at the beginning of a character.
+ */
+CHEWING_API int chewing_buffer_ByteToChar( ChewingContext *ctx, int byte_offset )
+{
+	int i, len = 0;
+
+	if ( !ctx )
+		return -1;
+
+	for ( i = 0; i <= ctx->output->chiSymbolBufLen; i++ ) {
+		if ( len == byte_offset )
+			return i;
+		if ( len > byte_offset || i == ctx->output->chiSymbolBufLen )
+			break;
+		len += strlen( (char *) ctx->output->chiSymbolBuf[ i ].s );
+	}
+	return -1;
+}
+
+/**
+ * @param ctx handle to Chewing IM context
+ * @param phone_index index of the syllable, as in chewing_get_phoneSeq()
+ *
+ * Returns the character offset of the syllable in the preedit buffer. When
+ * phone_index is the number of syllables, the length of the preedit buffer is
+ * returned. Returns -1 if phone_index is out of range.
+ */
+CHEWING_API int chewing_buffer_PhoneToChar( ChewingContext *ctx, int phone_index )
+{
+	int i, k = 0;
+
+	if ( !ctx )
+		return -1;
+
+	if ( phone_index < 0 )
+		return -1;
+	for ( i = 0; i < ctx->data->chiSymbolBufLen; i++ ) {
+		if ( ChewingIsChiAt( i, ctx->data ) ) {
+			if ( k == phone_index )
+				return i;
+			k++;
+		}
+	}
+	return ( k == phone_index ) ? i : -1;
+}
+
 CHEWING_API int chewing_aux_Check( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (ctx->output->bShowMsg);
 }
 
 CHEWING_API int chewing_aux_Length( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return -1;
+
 	return (ctx->output->bShowMsg ? ctx->output->showMsgLen : 0);
 }
 
 CHEWING_API char *chewing_aux_String( ChewingContext *ctx )
 {
 	int i;
-	char *msg = (char *) calloc(
+	char *msg;
+
+	if ( !ctx )
+		return NULL;
+
+	msg = (char *) calloc(
 		1 + ctx->output->showMsgLen,
 		sizeof(char) * MAX_UTF8_SIZE );
 	for ( i = 0; i < ctx->output->showMsgLen; ++i )
@@ -200,14 +414,41 @@ CHEWING_API char *chewing_aux_String( ChewingContext *ctx )
 
 }
 
+CHEWING_API const char *chewing_aux_String_static( ChewingContext *ctx )
+{
+	int i;
+
+	if ( !ctx )
+		return NULL;
+
+	ctx->aux_buf[ 0 ] = '\0';
+	for ( i = 0; i < ctx->output->showMsgLen; ++i )
+		strcat( ctx->aux_buf, (char *)(ctx->output->showMsg[ i ].s) );
+	return ctx->aux_buf;
+}
+
 CHEWING_API int chewing_keystroke_CheckIgnore( ChewingContext *ctx )
-{ 
-	return (ctx->output->keystrokeRtn & KEYSTROKE_IGNORE);
-} 
+{
+	if ( !ctx )
+		return -1;
+
+	return !!(ctx->output->keystrokeRtn & KEYSTROKE_IGNORE);
+}
 
 CHEWING_API int chewing_keystroke_CheckAbsorb( ChewingContext *ctx )
-{ 
-	return (ctx->output->keystrokeRtn & KEYSTROKE_ABSORB);
+{
+	if ( !ctx )
+		return -1;
+
+	return !!(ctx->output->keystrokeRtn & KEYSTROKE_ABSORB);
+}
+
+CHEWING_API int chewing_keystroke_CheckBell( ChewingContext *ctx )
+{
+	if ( !ctx )
+		return -1;
+
+	return !!(ctx->output->keystrokeRtn & KEYSTROKE_BELL);
 }
 
 CHEWING_API int chewing_kbtype_Total( ChewingContext *ctx UNUSED )
@@ -217,19 +458,27 @@ CHEWING_API int chewing_kbtype_Total( ChewingContext *ctx UNUSED )
 
 CHEWING_API void chewing_kbtype_Enumerate( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return;
+
 	ctx->kb_no = 0;
 }
 
 CHEWING_API int chewing_kbtype_hasNext( ChewingContext *ctx )
 {
+	if ( !ctx )
+		return 0;
+
 	return ctx->kb_no < KB_TYPE_NUM;
 }
 
-extern char *kb_type_str[];
-
 CHEWING_API char *chewing_kbtype_String( ChewingContext *ctx )
 {
 	char *s;
+
+	if ( !ctx )
+		return NULL;
+
 	if ( chewing_kbtype_hasNext( ctx ) ) {
 		s = strdup( kb_type_str[ ctx->kb_no ] );
 		ctx->kb_no++;
@@ -239,3 +488,20 @@ CHEWING_API char *chewing_kbtype_String( ChewingContext *ctx )
 	}
 	return s;
 }
+
+CHEWING_API const char *chewing_kbtype_String_static( ChewingContext *ctx )
+{
+	const char *s;
+
+	if ( !ctx )
+		return NULL;
+
+	if ( chewing_kbtype_hasNext( ctx ) ) {
+		s = kb_type_str[ ctx->kb_no ];
+		ctx->kb_no++;
+	}
+	else {
+		s = "";
+	}
+	return s;
+}
@@ -42,6 +42,16 @@ __forceinline void DEBUG_OUT( char* str, ... ){ }
 #define DEBUG_CHECKPOINT()
 #endif
 
+#define LOG( level, fmt, ... ) \
+	pgdata->logger( pgdata->loggerData, level, \
+		"[%s:%d %s] " fmt "\n", __FILE__, __LINE__, __func__, ##__VA_ARGS__ )
+
+#define LOG_VERBOSE( fmt, ... ) LOG( CHEWING_LOG_VERBOSE, fmt, ##__VA_ARGS__ )
+#define LOG_DEBUG( fmt, ... ) LOG( CHEWING_LOG_DEBUG, fmt, ##__VA_ARGS__ )
+#define LOG_INFO( fmt, ... ) LOG( CHEWING_LOG_INFO, fmt, ##__VA_ARGS__ )
+#define LOG_WARN( fmt, ... ) LOG( CHEWING_LOG_WARN, fmt, ##__VA_ARGS__ )
+#define LOG_ERROR( fmt, ... ) LOG( CHEWING_LOG_ERROR, fmt, ##__VA_ARGS__ )
+
 #define ALC(type, size) \
 	(type *) calloc( size, sizeof( type ) )
 
@@ -22,8 +22,17 @@
  *  	  phrase   frequency   zuin1 zuin2 zuin3 ... \n
  *  	  Output format : ( Sorted by zuin's uint16_t number )
  *  	  phrase   frequency   zuin1 zuin2 zuin3 ... \n
+ *
+ *	  Several dictionary files can be given. The same phrase with the same
+ *	  zuin in different lines is merged according to the -m option, and
+ *	  phrases listed in the file given by -x are dropped.
+ *
+ *	  Malformed lines are reported with their line and column numbers and
+ *	  skipped, or abort the program when --strict is given.
  */
 
+#include <limits.h>
+#include <stdarg.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
@@ -31,28 +40,52 @@
 #include "global.h"
 #include "global-private.h"
 #include "key2pho-private.h"
+#include "chewing-utf8-util.h"
+#include "chewing-private.h"
 #include "config.h"
 
 #define MAXLEN		149
-#define MAXZUIN		9
-#define MAX_FILE_NAME	(256)
+#define MAXZUIN		MAX_PHRASE_LEN
 
 #define IN_FILE		"phoneid.dic"
+#define MAX_DATA	420000L
+#define MAX_EXCLUDE	10000
 
 typedef struct {
 	char str[ MAXLEN ];
 	int freq;
 	uint16_t num[ MAXZUIN ];
+	long order;
 } RECORD;
 
-RECORD data[ 420000L ];
+/* how to handle the same phrase with the same zuin */
+enum {
+	MERGE_KEEP,	/* keep all of them */
+	MERGE_FIRST,	/* keep the first one */
+	MERGE_SUM,	/* sum up the frequency */
+	MERGE_MAX,	/* use the maximum frequency */
+};
+
+RECORD data[ MAX_DATA ];
 long nData;
 
+char *exclude[ MAX_EXCLUDE ];
+long nExclude;
+
+/* abort on malformed lines instead of skipping them */
+int strict;
+
 const char user_msg[] = 
 	"sort_dic -- read chinese phrase input and generate data file for chewing\n" \
 	"usage: \n" \
-		"\tsort_dic <tsi file name> or \n" \
+		"\tsort_dic [-m keep|first|sum|max] [-x <exclude file>] [--strict|--skip-invalid] \n" \
+		"\t\t<tsi file name> ... or \n" \
 		"\tsort_dic (default name is tsi.src) \n" \
+		"options: \n" \
+		"\t-m\thow to merge the same phrase in several lines (default keep) \n" \
+		"\t-x\tfile of phrases to be dropped, one phrase per line \n" \
+		"\t--strict\tstop at the first malformed line \n" \
+		"\t--skip-invalid\treport and skip malformed lines (default) \n" \
 		"This program creates three new files. \n" \
 		"1." DICT_FILE " \t-- main dictionary file \n" \
 		"2." PH_INDEX_FILE " \t-- index file of phrase \n" \
@@ -61,16 +94,74 @@ const char user_msg[] =
 extern const char *ph_pho[];
 /*extern uint16_t PhoneBg2Uint( const char *phone );*/
 
-void DataSetNum( long _index )
+void ParseError( const char *filename, long lineno, long column, const char *fmt, ... )
 {
-	char buf[ MAXLEN ], *p;
+	va_list ap;
+
+	fprintf( stderr, "%s:%ld:%ld: ", filename, lineno, column );
+	va_start( ap, fmt );
+	vfprintf( stderr, fmt, ap );
+	va_end( ap );
+	fprintf( stderr, "\n" );
+}
+
+/*
+ * Parse the original line instead of data[ _index ].str, so that the
+ * column numbers in error messages match the input file.
+ */
+int DataSetNum( long _index, const char *line, const char *filename, long lineno )
+{
+	char buf[ MAXLEN ], *p, *end;
+	const char *phrase;
+	long freq;
 	int i = 0;
 
-	strcpy( buf, data[ _index ].str );
-	strtok( buf, " \n\t" );
-	data[ _index ].freq = atoi( strtok( NULL, " \n\t" ) );
-	for ( p = strtok( NULL, " \n\t" ); p; p = strtok( NULL, " \n\t" ) ) 
-		data[ _index ].num[ i++ ] = UintFromPhone( p );
+	memset( data[ _index ].num, 0, sizeof( data[ _index ].num ) );
+
+	strcpy( buf, line );
+	p = strchr( buf, '#' );
+	if ( p )
+		*p = '\0';
+
+	phrase = strtok( buf, " \n\t" );
+	if ( ! phrase )
+		return 0;
+
+	p = strtok( NULL, " \n\t" );
+	if ( ! p ) {
+		ParseError( filename, lineno, strlen( line ), "missing frequency" );
+		return 0;
+	}
+	freq = strtol( p, &end, 10 );
+	if ( *end || freq < 0 || freq > INT_MAX ) {
+		ParseError( filename, lineno, p - buf + 1, "invalid frequency `%s'", p );
+		return 0;
+	}
+	data[ _index ].freq = freq;
+
+	for ( p = strtok( NULL, " \n\t" ); p; p = strtok( NULL, " \n\t" ) ) {
+		if ( i >= MAXZUIN ) {
+			ParseError( filename, lineno, p - buf + 1,
+				"more than %d bopomofo", MAXZUIN );
+			return 0;
+		}
+		data[ _index ].num[ i ] = UintFromPhone( p );
+		if ( ! data[ _index ].num[ i ] ) {
+			ParseError( filename, lineno, p - buf + 1, "invalid bopomofo `%s'", p );
+			return 0;
+		}
+		i++;
+	}
+	if ( i == 0 ) {
+		ParseError( filename, lineno, strlen( line ), "missing bopomofo" );
+		return 0;
+	}
+	if ( i != ueStrLen( phrase ) ) {
+		ParseError( filename, lineno, 1, "%d bopomofo for %d characters in `%s'",
+			i, ueStrLen( phrase ), phrase );
+		return 0;
+	}
+	return 1;
 }
 
 void DataStripSpace( long _index )
@@ -118,7 +209,12 @@ int CompRecord( const void *a, const void *b )
 		if ( cmp )
 			return cmp;
 	}
-	return ( ((RECORD *) b)->freq - ((RECORD *) a)->freq );
+	cmp = ((RECORD *) b)->freq - ((RECORD *) a)->freq;
+	if ( cmp )
+		return cmp;
+	/* qsort() is not stable; keep the order of tsi.src for the same freq */
+	return ( ((RECORD *) a)->order > ((RECORD *) b)->order ) -
+		( ((RECORD *) a)->order < ((RECORD *) b)->order );
 }
 
 int CompUint( long a, long b )
@@ -132,27 +228,188 @@ int CompUint( long a, long b )
 	return 0;
 }
 
-int main( int argc, char *argv[] )
+int CompMerge( const void *a, const void *b )
+{
+	long i;
+	int cmp;
+
+	for ( i = 0; i < MAXZUIN; i++ ) {
+		cmp = ((RECORD *) a)->num[ i ] - ((RECORD *) b)->num[ i ];
+		if ( cmp )
+			return cmp;
+	}
+	cmp = strcmp( ((RECORD *) a)->str, ((RECORD *) b)->str );
+	if ( cmp )
+		return cmp;
+	return ( ((RECORD *) a)->order > ((RECORD *) b)->order ) -
+		( ((RECORD *) a)->order < ((RECORD *) b)->order );
+}
+
+int CompStr( const void *a, const void *b )
+{
+	return strcmp( *(char * const *) a, *(char * const *) b );
+}
+
+int IsSameRecord( long a, long b )
+{
+	return CompUint( a, b ) == 0 && strcmp( data[ a ].str, data[ b ].str ) == 0;
+}
+
+void MergeData( int policy )
+{
+	long i, k;
+
+	if ( policy == MERGE_KEEP || nData == 0 )
+		return;
+
+	qsort( data, nData, sizeof( RECORD ), CompMerge );
+	for ( i = 1, k = 0; i < nData; i++ ) {
+		if ( IsSameRecord( i, k ) ) {
+			if ( policy == MERGE_SUM )
+				data[ k ].freq = ( data[ k ].freq > INT_MAX - data[ i ].freq ) ?
+					INT_MAX : data[ k ].freq + data[ i ].freq;
+			else if ( policy == MERGE_MAX && data[ i ].freq > data[ k ].freq )
+				data[ k ].freq = data[ i ].freq;
+			continue;
+		}
+		data[ ++k ] = data[ i ];
+	}
+	nData = k + 1;
+}
+
+void ReadExclude( const char *filename )
+{
+	FILE *file;
+	char buf[ MAXLEN ];
+	char *p;
+
+	file = fopen( filename, "r" );
+	if ( !file ) {
+		fprintf( stderr, "Error opening %s for reading!\n", filename );
+		exit( -1 );
+	}
+	while ( fgets( buf, sizeof( buf ), file ) ) {
+		p = strtok( buf, " \n\t" );
+		if ( !p || p[ 0 ] == '#' )
+			continue;
+		if ( nExclude >= MAX_EXCLUDE ) {
+			fprintf( stderr, "Too many phrases in %s!\n", filename );
+			exit( -1 );
+		}
+		exclude[ nExclude++ ] = strdup( p );
+	}
+	fclose( file );
+	qsort( exclude, nExclude, sizeof( char * ), CompStr );
+}
+
+int IsExcluded( const char *str )
+{
+	return nExclude &&
+		bsearch( &str, exclude, nExclude, sizeof( char * ), CompStr );
+}
+
+void ReadData( const char *filename )
 {
 	FILE *infile;
+	char line[ MAXLEN ];
+	long lineno = 0;
+	int ch;
+
+	infile = fopen( filename, "r" );
+	if ( !infile ) {
+		fprintf ( stderr, "Error opening %s for reading!\n", filename );
+		exit( -1 );
+	}
+
+	while ( fgets( line, MAXLEN, infile ) ) {
+		++lineno;
+		if ( ! strchr( line, '\n' ) && ! feof( infile ) ) {
+			ParseError( filename, lineno, MAXLEN - 1, "line too long" );
+			if ( strict )
+				exit( -1 );
+			/* skip the rest of the line */
+			while ( ( ch = fgetc( infile ) ) != EOF && ch != '\n' )
+				;
+			continue;
+		}
+
+		strcpy( data[ nData ].str, line );
+		DataStripSpace( nData );
+		/* Ignore '#' comment and blank line for tsi.src */
+		if ( data[ nData ].str[0] == '\n' || data[ nData ].str[0] == '\0' )
+			continue;
+		if ( ! DataSetNum( nData, line, filename, lineno ) ) {
+			if ( strict )
+				exit( -1 );
+			continue;
+		}
+		DataStripAll( nData );
+		if ( IsExcluded( data[ nData ].str ) )
+			continue;
+		data[ nData ].order = nData;
+		nData++;
+		if ( nData >= MAX_DATA ) {
+			fprintf( stderr, "Too many phrases!\n" );
+			exit( -1 );
+		}
+	}
+	fclose( infile );
+}
+
+int main( int argc, char *argv[] )
+{
 	FILE *dictfile, *treedata, *ph_index;
-	char in_file[ MAX_FILE_NAME ] = "tsi.src";
+	const char *exclude_file = NULL;
+	int policy = MERGE_KEEP;
+	int first_file;
 	long i, k;
 	int tmp;
 #ifdef USE_BINARY_DATA
 	unsigned char size;
 #endif
 
-	if ( argc < 2 ) 
-		printf( user_msg );
-	else 
-		strcpy( in_file, argv[ 1 ] );
+	for ( first_file = 1; first_file < argc; first_file++ ) {
+		if ( ( ! strcmp( argv[ first_file ], "-m" ) ||
+		       ! strcmp( argv[ first_file ], "-x" ) ) && first_file + 1 == argc ) {
+			fprintf( stderr, "Missing value for %s!\n", argv[ first_file ] );
+			fprintf( stderr, "%s", user_msg );
+			exit( -1 );
+		}
+		if ( ! strcmp( argv[ first_file ], "-m" ) ) {
+			++first_file;
+			if ( ! strcmp( argv[ first_file ], "keep" ) )
+				policy = MERGE_KEEP;
+			else if ( ! strcmp( argv[ first_file ], "first" ) )
+				policy = MERGE_FIRST;
+			else if ( ! strcmp( argv[ first_file ], "sum" ) )
+				policy = MERGE_SUM;
+			else if ( ! strcmp( argv[ first_file ], "max" ) )
+				policy = MERGE_MAX;
+			else {
+				fprintf( stderr, "Unknown merge policy %s!\n", argv[ first_file ] );
+				exit( -1 );
+			}
+		}
+		else if ( ! strcmp( argv[ first_file ], "-x" ) )
+			exclude_file = argv[ ++first_file ];
+		else if ( ! strcmp( argv[ first_file ], "--strict" ) )
+			strict = 1;
+		else if ( ! strcmp( argv[ first_file ], "--skip-invalid" ) )
+			strict = 0;
+		else
+			break;
+	}
 
-	infile = fopen( in_file, "r" );
+	if ( exclude_file )
+		ReadExclude( exclude_file );
 
-	if ( !infile ) {
-		fprintf ( stderr, "Error opening %s for reading!\n", in_file );
-		exit( -1 );
+	if ( first_file == argc ) {
+		printf( user_msg );
+		ReadData( "tsi.src" );
+	}
+	else {
+		for ( i = first_file; i < argc; i++ )
+			ReadData( argv[ i ] );
 	}
 
 #ifdef USE_BINARY_DATA
@@ -169,15 +426,7 @@ int main( int argc, char *argv[] )
 		exit( -1 );
 	}
 
-	while ( fgets( data[ nData ].str, MAXLEN, infile ) ) {
-		DataStripSpace( nData );
-		/* Ignore '#' comment for tsi.src */
-		if ( data[ nData ].str[0] == '\n' )
-			continue;
-		DataSetNum( nData );
-		DataStripAll( nData );
-		nData++;
-	}
+	MergeData( policy );
 	qsort( data, nData, sizeof( RECORD ), CompRecord );
 
 	for ( i = 0; i < nData - 1; i++ ) {
@@ -217,11 +466,10 @@ int main( int argc, char *argv[] )
 		if ( ( i > 0 ) && ( CompUint( i, i - 1 ) == 0 ) )
 			continue;
 
-		for ( k = 0; data[ i ].num[ k ]; k++ )
+		for ( k = 0; k < MAXZUIN && data[ i ].num[ k ]; k++ )
 			fprintf (treedata, "%hu ", data[ i ].num[ k ] );
 		fprintf( treedata, "0\n" );
 	}
-	fclose( infile );
 	fclose( ph_index );
 	fclose( dictfile );
 	fclose( treedata );
@@ -41,7 +41,8 @@ typedef struct {
 
 typedef struct tagRecordNode {
 	int *arrIndex;		/* the index array of the things in "interval" */
-	int nInter, score;
+	int nInter;
+	long long score;
 	struct tagRecordNode *next;
 	int nMatchCnnct;	/* match how many Cnnct. */
 } RecordNode;
@@ -75,18 +76,6 @@ static int PhraseIntervalIntersect(PhraseIntervalType in1, PhraseIntervalType in
 	return ( max( in1.from, in2.from ) < min( in1.to, in2.to ) );
 }
 
-#if 0
-/** @brief check for intersection of two intervals and return it */
-static int GetIntersection( IntervalType in1, IntervalType in2, IntervalType *in3 )
-{
-	in3->from = max( in1.from, in2.from );
-	in3->to = min( in1.to, in2.to );
-	if ( in3->from < in3->to )
-		return 1;
-	return 0;
-}
-#endif
-
 void TerminateTree( ChewingData *pgdata )
 {
 #ifdef USE_BINARY_DATA
@@ -311,8 +300,10 @@ int TreeFindPhrase( ChewingData *pgdata, int begin, int end, const uint16_t *pho
 				break;
 		}
 		/* if not found any word then fail. */
-		if ( child == -1 || child > pgdata->static_data.tree[ tree_p ].child_end )
+		if ( child == -1 || child > pgdata->static_data.tree[ tree_p ].child_end ) {
+			LOG_VERBOSE( "No phrase in [%d, %d]", begin, end );
 			return -1;
+		}
 		else {
 			tree_p = child;
 		}
@@ -474,26 +465,6 @@ static void SetInfo( int len, TreeDataType *ptd )
 	}
 }
 
-#if 0
-static int CompLen( IntervalType *pa, IntervalType *pb )
-{
-	return ( ( pa->to - pa->from ) - ( pb->to - pb->from ) );
-}
-
-static int CompLenDescend( IntervalType *pa, IntervalType *pb )
-{
-	return ( ( pb->to - pb->from ) - ( pa->to - pa->from ) );
-}
-
-static int CompFrom( IntervalType *pa, IntervalType *pb )
-{
-	int cmp = pa->from - pb->from;
-	if ( cmp )
-		return cmp;
-	return ( pa->to - pb->to );
-}
-#endif
-
 /* 
  * First we compare the 'nMatchCnnct'.
  * If the values are the same, we will compare the 'score'
@@ -504,7 +475,12 @@ static int CompRecord( const RecordNode **pa, const RecordNode **pb )
 
 	if ( diff )
 		return diff;
-	return ( (*pb)->score - (*pa)->score );
+	/* the difference of scores might not fit in int */
+	if ( (*pb)->score > (*pa)->score )
+		return 1;
+	if ( (*pb)->score < (*pa)->score )
+		return -1;
+	return 0;
 }
 
 
@@ -676,9 +652,14 @@ static int rule_smallest_lenvariance( int *record, int nRecord, TreeDataType *pt
 	return -score;
 }
 
-static int rule_largest_freqsum( int *record, int nRecord, TreeDataType *ptd )
+/*
+ * Frequencies come from the dictionary and the user phrase file unchecked,
+ * so their sum is kept in long long to avoid overflow.
+ */
+static long long rule_largest_freqsum( int *record, int nRecord, TreeDataType *ptd )
 {
-	int i, score = 0;
+	int i;
+	long long score = 0;
 	PhraseIntervalType inter;
 
 	for ( i = 0; i < nRecord; i++ ) {
@@ -693,9 +674,9 @@ static int rule_largest_freqsum( int *record, int nRecord, TreeDataType *ptd )
 	return score;
 }
 
-static int LoadPhraseAndCountScore( int *record, int nRecord, TreeDataType *ptd )
+static long long LoadPhraseAndCountScore( int *record, int nRecord, TreeDataType *ptd )
 {
-	int total_score = 0;
+	long long total_score = 0;
 	/* NOTE: the balance factor is tuneable */
 	if (nRecord) {
 		total_score += 1000*rule_largest_sum( record, nRecord, ptd );
@@ -917,7 +898,7 @@ static void ShowList( TreeDataType *ptd )
 		}
 		DEBUG_OUT(
 			"\n"
-			   "      score : %d , nMatchCnnct : %d\n",
+			   "      score : %lld , nMatchCnnct : %d\n",
 			p->score,
 			p->nMatchCnnct );
 	}
@@ -965,6 +946,8 @@ int Phrasing(
 {
 	TreeDataType treeData;
 
+	LOG_DEBUG( "nPhoneSeq = %d, nSelect = %d", nPhoneSeq, nSelect );
+
 	InitPhrasing( &treeData );
 
 	FindInterval( 
@@ -994,6 +977,7 @@ int Phrasing(
 		nPhoneSeq, 
 		selectStr, selectInterval, nSelect, &treeData );
 	SaveDispInterval( ppo, &treeData );
+	LOG_DEBUG( "Phrasing result: %s", ppo->chiBuf );
 
 	/* free "phrase" */
 	CleanUpMem( &treeData );
@@ -24,8 +24,6 @@
 #include "userphrase-private.h"
 #include "private.h"
 
-static HASH_ITEM *pItemLast;
-
 #if 0
 static int DeltaFreq( int recentTime )
 {
@@ -131,13 +129,63 @@ static int UpdateFreq( int freq, int maxfreq, int origfreq, int deltatime )
 	}
 }
 
+/* least recently used first, then least frequently used */
+static int CompEvictOrder( const void *a, const void *b )
+{
+	const UserPhraseData *pa = &( *(HASH_ITEM * const *) a )->data;
+	const UserPhraseData *pb = &( *(HASH_ITEM * const *) b )->data;
+
+	if ( pa->recentTime != pb->recentTime )
+		return pa->recentTime < pb->recentTime ? -1 : 1;
+	if ( pa->userfreq != pb->userfreq )
+		return pa->userfreq < pb->userfreq ? -1 : 1;
+	return 0;
+}
+
+int UserLimitPhrase( ChewingData *pgdata, int max )
+{
+	HASH_ITEM **items, *pItem;
+	int count, i, removed = 0;
+
+	count = pgdata->static_data.n_hash_item;
+	if ( count <= max )
+		return 0;
+
+	items = ALC( HASH_ITEM *, count );
+	if ( ! items )
+		return 0;
+	for ( i = 0, pItem = HashNext( pgdata, NULL );
+	      i < count && pItem; ++i, pItem = HashNext( pgdata, pItem ) ) {
+		items[ i ] = pItem;
+	}
+	count = i;
+	qsort( items, count, sizeof( items[ 0 ] ), CompEvictOrder );
+
+	for ( i = 0; i < count - max; ++i ) {
+		if ( UserRemovePhrase( pgdata, items[ i ]->data.phoneSeq, items[ i ]->data.wordSeq ) )
+			++removed;
+	}
+	free( items );
+	return removed;
+}
+
 int UserUpdatePhrase( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] )
 {
 	HASH_ITEM *pItem;
 	UserPhraseData data;
-	int len;
+	int len, max, phone_len;
 
 	len = ueStrLen( (char *) wordSeq );
+	/*
+	 * Callers working on a buffer with symbols might pass fewer words than
+	 * phones. HashInsert() would then find an existing item through the
+	 * truncated phoneSeq and the new data would be leaked.
+	 */
+	for ( phone_len = 0; phoneSeq[ phone_len ] != 0; ++phone_len )
+		;
+	if ( len == 0 || phone_len != len )
+		return USER_UPDATE_FAIL;
+
 	pItem = HashFindEntry( pgdata, phoneSeq, wordSeq );
 	if ( ! pItem ) {
 		if ( ! AlcUserPhraseSeq( &data, len, strlen( wordSeq ) ) ) {
@@ -154,6 +202,12 @@ int UserUpdatePhrase( ChewingData *pgdata, const uint16_t phoneSeq[], const char
 
 		data.userfreq = data.origfreq;
 		data.recentTime = pgdata->static_data.chewing_lifetime;
+
+		/* make room for the new phrase */
+		max = pgdata->ext_config.maxUserPhrase;
+		if ( max > 0 && pgdata->static_data.n_hash_item >= max )
+			UserLimitPhrase( pgdata, max - 1 );
+
 		pItem = HashInsert( pgdata, &data );
 		HashModify( pgdata, pItem );
 		return USER_UPDATE_INSERT;
@@ -171,19 +225,93 @@ int UserUpdatePhrase( ChewingData *pgdata, const uint16_t phoneSeq[], const char
 	}
 }
 
+int UserSuppressPhrase( ChewingData *pgdata, const char wordSeq[], int suppress )
+{
+	ChewingStaticData *static_data = &pgdata->static_data;
+	char **list;
+	unsigned int i;
+
+	for ( i = 0; i < static_data->n_suppressed_phrase; ++i ) {
+		if ( ! strcmp( static_data->suppressed_phrase[ i ], wordSeq ) )
+			break;
+	}
+
+	if ( ! suppress ) {
+		if ( i < static_data->n_suppressed_phrase ) {
+			free( static_data->suppressed_phrase[ i ] );
+			static_data->suppressed_phrase[ i ] =
+				static_data->suppressed_phrase[ --static_data->n_suppressed_phrase ];
+		}
+		return 0;
+	}
+
+	if ( i < static_data->n_suppressed_phrase )
+		return 0;
+
+	list = realloc( static_data->suppressed_phrase,
+		sizeof( char * ) * ( static_data->n_suppressed_phrase + 1 ) );
+	if ( ! list )
+		return -1;
+	static_data->suppressed_phrase = list;
+
+	list[ static_data->n_suppressed_phrase ] = strdup( wordSeq );
+	if ( ! list[ static_data->n_suppressed_phrase ] )
+		return -1;
+	++static_data->n_suppressed_phrase;
+	return 0;
+}
+
+int UserIsPhraseSuppressed( ChewingData *pgdata, const char wordSeq[] )
+{
+	unsigned int i;
+
+	for ( i = 0; i < pgdata->static_data.n_suppressed_phrase; ++i ) {
+		if ( ! strcmp( pgdata->static_data.suppressed_phrase[ i ], wordSeq ) )
+			return 1;
+	}
+	return 0;
+}
+
+void TerminateUserPhraseSuppression( ChewingData *pgdata )
+{
+	unsigned int i;
+
+	for ( i = 0; i < pgdata->static_data.n_suppressed_phrase; ++i )
+		free( pgdata->static_data.suppressed_phrase[ i ] );
+	free( pgdata->static_data.suppressed_phrase );
+	pgdata->static_data.suppressed_phrase = NULL;
+	pgdata->static_data.n_suppressed_phrase = 0;
+}
+
+int UserRemovePhrase( ChewingData *pgdata, const uint16_t phoneSeq[], const char wordSeq[] )
+{
+	HASH_ITEM *pItem;
+
+	/* userphrase_last might point to the removed item */
+	pgdata->static_data.userphrase_last = NULL;
+
+	/* keep the enumeration valid when its next item is removed */
+	pItem = HashFindEntry( pgdata, phoneSeq, wordSeq );
+	if ( pItem && pItem == pgdata->static_data.userphrase_enum )
+		pgdata->static_data.userphrase_enum = HashNext( pgdata, pItem );
+
+	return HashRemove( pgdata, phoneSeq, wordSeq );
+}
+
 UserPhraseData *UserGetPhraseFirst( ChewingData *pgdata, const uint16_t phoneSeq[] )
 {
-	pItemLast = HashFindPhonePhrase( pgdata, phoneSeq, NULL );
-	if ( ! pItemLast ) 
+	pgdata->static_data.userphrase_last = HashFindPhonePhrase( pgdata, phoneSeq, NULL );
+	if ( ! pgdata->static_data.userphrase_last ) 
 		return NULL;
-	return &( pItemLast->data );
+	return &( pgdata->static_data.userphrase_last->data );
 }
 
 UserPhraseData *UserGetPhraseNext( ChewingData *pgdata, const uint16_t phoneSeq[] )
 {
-	pItemLast = HashFindPhonePhrase( pgdata, phoneSeq, pItemLast );
-	if ( ! pItemLast )
+	pgdata->static_data.userphrase_last = HashFindPhonePhrase(
+		pgdata, phoneSeq, pgdata->static_data.userphrase_last );
+	if ( ! pgdata->static_data.userphrase_last )
 		return NULL;
-	return &( pItemLast->data );
+	return &( pgdata->static_data.userphrase_last->data );
 }
 
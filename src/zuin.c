@@ -38,6 +38,7 @@
  *	ZUIN_COMMIT
  *	ZUIN_KEY_ERROR
  *	ZUIN_ERROR
+ *	ZUIN_CORRECT
  */
 static int IsHsuPhoEndKey( int pho_inx[], int key )
 {
@@ -103,6 +104,97 @@ static int IsDefPhoEndKey( int key, int kbtype )
 	return 0;
 }
 
+/* rows of the physical keyboard, used to find the adjacent keys */
+static const char * const key_row[] = {
+	"1234567890-=",
+	"qwertyuiop[]",
+	"asdfghjkl;'",
+	"zxcvbnm,./",
+};
+
+/* the same rows, typed with the Dvorak layout of the system */
+static const char * const dvorak_key_row[] = {
+	"1234567890[]",
+	"',.pyfgcrl/=",
+	"aoeuidhtns-",
+	";qjkxbmwvz",
+};
+
+static int HasWord( ChewingData *pgdata, const int pho_inx[] )
+{
+	Word tempword;
+
+	return GetCharFirst( pgdata, &tempword, UintFromPhoneInx( pho_inx ) );
+}
+
+/*
+ * Replace one zhuin of type with that of its adjacent keys on the same row.
+ * Return 1 and keep the replacement in pho_inx if it has words.
+ */
+static int CorrectAdjacentKey( ChewingData *pgdata, ZuinData *pZuin, int type )
+{
+	int orig = pZuin->pho_inx[ type ];
+	int row, col, len, i, inx;
+	const int offset[] = { -1, 1 };
+	const char * const *rows = key_row;
+
+	if ( pZuin->kbtype == KB_DVORAK || pZuin->kbtype == KB_DVORAK_HSU )
+		rows = dvorak_key_row;
+
+	for ( row = 0; row < (int) ARRAY_SIZE( key_row ); ++row ) {
+		len = strlen( rows[ row ] );
+		for ( col = 0; col < len; ++col ) {
+			if ( PhoneInxFromKey( rows[ row ][ col ], type, pZuin->kbtype, 1 ) != orig )
+				continue;
+			for ( i = 0; i < (int) ARRAY_SIZE( offset ); ++i ) {
+				if ( col + offset[ i ] < 0 || col + offset[ i ] >= len )
+					continue;
+				inx = PhoneInxFromKey(
+					rows[ row ][ col + offset[ i ] ], type, pZuin->kbtype, 1 );
+				if ( inx == 0 || inx == orig )
+					continue;
+				pZuin->pho_inx[ type ] = inx;
+				if ( HasWord( pgdata, pZuin->pho_inx ) )
+					return 1;
+			}
+		}
+	}
+	pZuin->pho_inx[ type ] = orig;
+	return 0;
+}
+
+/*
+ * Try the near-miss syllables of a syllable without words: other tones
+ * first, then the adjacent keys of the initial, medial and final.
+ * Return 1 and keep the correction in pho_inx if one is found.
+ */
+static int CorrectPhone( ChewingData *pgdata, ZuinData *pZuin )
+{
+	int orig = pZuin->pho_inx[ 3 ];
+	int type, tone;
+
+	for ( tone = 0; tone <= 4; ++tone ) {
+		if ( tone == orig )
+			continue;
+		pZuin->pho_inx[ 3 ] = tone;
+		if ( HasWord( pgdata, pZuin->pho_inx ) )
+			return 1;
+	}
+	pZuin->pho_inx[ 3 ] = orig;
+
+	/* the layout of pinyin has nothing to do with the bopomofo */
+	if ( pZuin->kbtype == KB_HANYU_PINYIN )
+		return 0;
+
+	for ( type = 0; type < 3; ++type ) {
+		if ( pZuin->pho_inx[ type ] == 0 )
+			continue;
+		if ( CorrectAdjacentKey( pgdata, pZuin, type ) )
+			return 1;
+	}
+	return 0;
+}
+
 static int EndKeyProcess( ChewingData *pgdata, ZuinData *pZuin, int key, int searchTimes )
 {
 	uint16_t u16Pho;
@@ -133,6 +225,11 @@ static int EndKeyProcess( ChewingData *pgdata, ZuinData *pZuin, int key, int sea
 	}
 	u16Pho = UintFromPhoneInx( pZuin->pho_inx );
 	if ( GetCharFirst( pgdata, &tempword, u16Pho ) == 0 ) {
+		if ( pgdata->ext_config.bPhoneCorrection && CorrectPhone( pgdata, pZuin ) ) {
+			pZuin->phone = UintFromPhoneInx( pZuin->pho_inx );
+			memset( pZuin->pho_inx, 0, sizeof( pZuin->pho_inx ) );
+			return ZUIN_CORRECT;
+		}
 		ZuinRemoveAll( pZuin );
 		return ZUIN_NO_WORD;
 	}
@@ -644,7 +741,8 @@ int ZuinRemoveLast( ZuinData *pZuin )
 	int i;
 	if ( pZuin->kbtype >= KB_HANYU_PINYIN ) {
 		i = strlen( pZuin->pinYinData.keySeq );
-		pZuin->pinYinData.keySeq[ i - 1 ] = '\0';
+		if ( i > 0 )
+			pZuin->pinYinData.keySeq[ i - 1 ] = '\0';
 	} else {
 		for ( i = 3; i >= 0; i-- ) {
 			if ( pZuin->pho_inx[ i ] ) {
@@ -17,6 +17,7 @@
 /* Only used by calculating char position */
 #include "internal/chewing-utf8-util.h"
 
+#include <stdarg.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
@@ -59,6 +60,9 @@
 #define CTRL_8		KEY_F(8)
 #define CTRL_9		KEY_F(9)
 
+/* Size of the keystrokes and the commit string of one material entry */
+#define MATERIAL_BUF_LEN 1024
+
 /* Spacing */
 #define FILL_LINE  "--------------------------------------------------------"
 #define FILL_BLANK "                                                               "
@@ -66,6 +70,10 @@
 static int hasColor = 0;
 static int selKey_define[ 11 ] = {'1','2','3','4','5','6','7','8','9','0',0}; /* Default */
 
+static FILE *fmaterial = NULL;
+static char material_keystroke[ MATERIAL_BUF_LEN ];
+static char material_commit[ MATERIAL_BUF_LEN ];
+
 void drawline( int x, int y )
 {
 	move( x, y );
@@ -265,6 +273,60 @@ void show_commit_string( ChewingContext *ctx )
 	}
 }
 
+/*
+ * Write the keystroke to the output file, and remember it for the material
+ * entry if needed.
+ */
+void record_keystroke( FILE *fout, const char *fmt, ... )
+{
+	char buf[ 16 ];
+	va_list ap;
+
+	va_start( ap, fmt );
+	vsnprintf( buf, sizeof( buf ), fmt, ap );
+	va_end( ap );
+
+	fprintf( fout, "%s", buf );
+
+	if ( fmaterial && strlen( material_keystroke ) + strlen( buf ) <
+			sizeof( material_keystroke ) )
+		strcat( material_keystroke, buf );
+}
+
+/*
+ * Collect the commit string. When the keystroke is Enter, write the collected
+ * keystrokes and commit string as an entry of materials.txt, which can be
+ * replayed by simulate.
+ */
+void record_material( ChewingContext *ctx, int is_enter )
+{
+	char *commit_string;
+	char *pos;
+
+	if ( ! fmaterial )
+		return;
+
+	if ( chewing_commit_Check( ctx ) ) {
+		commit_string = chewing_commit_String( ctx );
+		if ( strlen( material_commit ) + strlen( commit_string ) <
+				sizeof( material_commit ) )
+			strcat( material_commit, commit_string );
+		free( commit_string );
+	}
+
+	if ( ! is_enter || ! chewing_commit_Check( ctx ) )
+		return;
+
+	/* simulate treats the first <E> as the end of keystrokes */
+	pos = strstr( material_keystroke, "<E>" );
+	if ( pos && pos[ 3 ] == '\0' && material_keystroke[ 0 ] != '#' &&
+			material_keystroke[ 0 ] != ' ' )
+		fprintf( fmaterial, "%s\t%s\n", material_keystroke, material_commit );
+
+	material_keystroke[ 0 ] = '\0';
+	material_commit[ 0 ] = '\0';
+}
+
 int main( int argc, char *argv[] )
 {
 	ChewingContext *ctx;
@@ -274,7 +336,7 @@ int main( int argc, char *argv[] )
 	int add_phrase_length;
 
 	if ( argc < 2 ) {
-		fprintf( stderr, "usage: genkeystroke filename\n" );
+		fprintf( stderr, "usage: genkeystroke filename [materials]\n" );
 		exit( 1 );
 	}
 	else {
@@ -283,6 +345,13 @@ int main( int argc, char *argv[] )
 			fprintf( stderr, "Error: failed to open %s\n", argv[ 1 ] );
 			exit( 1 );
 		}
+		if ( argc > 2 ) {
+			fmaterial = fopen( argv[ 2 ], "w" );
+			if ( ! fmaterial ) {
+				fprintf( stderr, "Error: failed to open %s\n", argv[ 2 ] );
+				exit( 1 );
+			}
+		}
 	}
 
 	/* Initialize curses library */
@@ -331,59 +400,67 @@ int main( int argc, char *argv[] )
 		switch ( ch ) {
 			case KEY_LEFT:
 				chewing_handle_Left( ctx );
-				fprintf( fout, "<L>" );
+				record_keystroke( fout, "<L>" );
 				break;
 			case KEY_SLEFT:
 				chewing_handle_ShiftLeft( ctx );
-				fprintf( fout, "<SL>" );
+				record_keystroke( fout, "<SL>" );
 				break;
 			case KEY_RIGHT:
 				chewing_handle_Right( ctx );
-				fprintf( fout, "<R>" );
+				record_keystroke( fout, "<R>" );
 				break;
 			case KEY_SRIGHT:
 				chewing_handle_ShiftRight( ctx );
-				fprintf( fout, "<SR>" );
+				record_keystroke( fout, "<SR>" );
 				break;
 			case KEY_UP:
 				chewing_handle_Up( ctx );
-				fprintf( fout, "<U>" );
+				record_keystroke( fout, "<U>" );
 				break;
 			case KEY_DOWN:
 				chewing_handle_Down( ctx );
-				fprintf( fout, "<D>" );
+				record_keystroke( fout, "<D>" );
 				break;
 			case KEY_SPACE:
 				chewing_handle_Space( ctx );
-				fprintf( fout, " " );
+				record_keystroke( fout, " " );
 				break;
 			case KEY_ENTER:
 				chewing_handle_Enter( ctx );
-				fprintf( fout, "<E>" );
+				record_keystroke( fout, "<E>" );
 				break;
 			case KEY_BACKSPACE:
 				chewing_handle_Backspace( ctx );
-				fprintf( fout, "<B>" );
+				record_keystroke( fout, "<B>" );
 				break;
 			case KEY_ESC:
 				chewing_handle_Esc( ctx );
-				fprintf( fout, "<EE>" );
+				record_keystroke( fout, "<EE>" );
 				break;
 			case KEY_DC:
 				chewing_handle_Del( ctx );
-				fprintf( fout, "<DC>" );
+				record_keystroke( fout, "<DC>" );
 				break;
 			case KEY_HOME:
 				chewing_handle_Home( ctx );
-				fprintf( fout, "<H>" );
+				record_keystroke( fout, "<H>" );
 				break;
 			case KEY_END:
 				chewing_handle_End( ctx );
-				fprintf( fout, "<EN>" );
+				record_keystroke( fout, "<EN>" );
 				break;
 			case KEY_TAB:
 				chewing_handle_Tab( ctx );
-				fprintf( fout, "<T>" );
+				record_keystroke( fout, "<T>" );
+				break;
+			case KEY_PPAGE:
+				chewing_handle_PageUp( ctx );
+				record_keystroke( fout, "<PU>" );
+				break;
+			case KEY_NPAGE:
+				chewing_handle_PageDown( ctx );
+				record_keystroke( fout, "<PD>" );
 				break;
 			case CTRL_0:
 			case CTRL_1:
@@ -397,11 +474,11 @@ int main( int argc, char *argv[] )
 			case CTRL_9:
 				add_phrase_length = ( ch - CTRL_0 + '0' );
 				chewing_handle_CtrlNum( ctx, add_phrase_length );
-				fprintf( fout, "<C%c>", add_phrase_length );
+				record_keystroke( fout, "<C%c>", add_phrase_length );
 				break;
 			case KEY_CTRL_('B'): /* emulate CapsLock */
 				chewing_handle_Capslock( ctx );
-				fprintf( fout, "<CB>");
+				record_keystroke( fout, "<CB>");
 				break;
 			case KEY_CTRL_('D'):
 				goto end;
@@ -414,11 +491,12 @@ int main( int argc, char *argv[] )
 			default:
 				chewing_handle_Default( ctx, (char) ch );
 				if ( ch != '<' && ch != '>' )
-					fprintf( fout, "%c", (char) ch );
+					record_keystroke( fout, "%c", (char) ch );
 				else
-					fprintf( fout, "<%c>", (char) ch );
+					record_keystroke( fout, "<%c>", (char) ch );
 				break;
 		}
+		record_material( ctx, ch == KEY_ENTER );
 		drawline( 0, 0 );
 		drawline( 2, 0 );
 		show_interval_buffer( 3, 0, ctx );
@@ -447,6 +525,8 @@ end:
 
 	fprintf( fout, "\n" );
 	fclose( fout );
+	if ( fmaterial )
+		fclose( fmaterial );
 	return 0;
 }
 
@@ -14,8 +14,10 @@
 
 #include <stdlib.h>
 #include <stdio.h>
+#include <string.h>
 
 #include "chewing.h"
+#include "chewing-utf8-util.h"
 #include "plat_types.h"
 #include "hash-private.h"
 #include "test.h"
@@ -80,7 +82,6 @@ void test_select_candidate_phrase_choice_rearward()
 
 	static const char *CAND_2[] = {
 		"上來",
-		"快上", // XXX: bug?
 	};
 
 	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
@@ -110,6 +111,516 @@ void test_select_candidate_phrase_choice_rearward()
 	chewing_Terminate();
 }
 
+void test_phrase_with_three_syllables()
+{
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	type_keystoke_by_string( ctx, "dj94g;4x96" ); // ㄎㄨㄞˋㄕㄤˋㄌㄞˊ
+	ok_preedit_buffer( ctx, "快上來" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_cand_open_close()
+{
+	static const char *CAND_1[] = {
+		"一上來",
+		"移上來",
+	};
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	ok( chewing_cand_open( ctx ) == -1,
+		"chewing_cand_open shall fail with empty buffer" );
+	ok( chewing_cand_choose_by_index( ctx, 0 ) == -1,
+		"chewing_cand_choose_by_index shall fail when window is closed" );
+
+	type_keystoke_by_string( ctx, "u6g;4x96<L><L><L>" ); // ㄧˊㄕㄤˋㄌㄞˊ
+
+	ok( chewing_cand_open( ctx ) == 0, "chewing_cand_open shall return 0" );
+	ok_candidate( ctx, CAND_1, ARRAY_SIZE( CAND_1 ) );
+	ok( chewing_cand_open( ctx ) == 0,
+		"chewing_cand_open shall return 0 when window is open" );
+	ok_candidate( ctx, CAND_1, ARRAY_SIZE( CAND_1 ) );
+
+	ok( chewing_cand_close( ctx ) == 0, "chewing_cand_close shall return 0" );
+	ok( chewing_cand_TotalChoice( ctx ) == 0, "window shall be closed" );
+
+	chewing_cand_open( ctx );
+	ok( chewing_cand_choose_by_index( ctx, ARRAY_SIZE( CAND_1 ) ) == -1,
+		"chewing_cand_choose_by_index shall fail with invalid index" );
+	ok( chewing_cand_choose_by_index( ctx, 1 ) == 0,
+		"chewing_cand_choose_by_index shall return 0" );
+	ok( chewing_cand_TotalChoice( ctx ) == 0, "window shall be closed" );
+	ok_preedit_buffer( ctx, CAND_1[1] );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_cand_list()
+{
+	static const char *CAND_1[] = {
+		"一上來",
+		"移上來",
+	};
+
+	static const char *CAND_2[] = {
+		"移上",
+	};
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	type_keystoke_by_string( ctx, "u6g;4x96<L><L><L>" ); // ㄧˊㄕㄤˋㄌㄞˊ
+
+	ok( chewing_cand_list_next( ctx ) == -1,
+		"chewing_cand_list_next shall fail when window is closed" );
+	ok( chewing_cand_list_has_next( ctx ) == 0,
+		"chewing_cand_list_has_next shall return 0 when window is closed" );
+
+	chewing_cand_open( ctx );
+	ok_candidate( ctx, CAND_1, ARRAY_SIZE( CAND_1 ) );
+	ok( chewing_cand_list_has_prev( ctx ) == 0,
+		"there shall be no longer phrase" );
+	ok( chewing_cand_list_prev( ctx ) == -1,
+		"chewing_cand_list_prev shall fail at the longest phrase" );
+
+	ok( chewing_cand_list_has_next( ctx ) == 1, "there shall be shorter phrase" );
+	ok( chewing_cand_list_next( ctx ) == 0,
+		"chewing_cand_list_next shall return 0" );
+	ok_candidate( ctx, CAND_2, ARRAY_SIZE( CAND_2 ) );
+	ok( chewing_cand_list_has_prev( ctx ) == 1, "there shall be longer phrase" );
+
+	ok( chewing_cand_list_last( ctx ) == 0,
+		"chewing_cand_list_last shall return 0" );
+	ok( chewing_cand_list_has_next( ctx ) == 0,
+		"there shall be no shorter phrase" );
+	ok( chewing_cand_list_next( ctx ) == -1,
+		"chewing_cand_list_next shall fail at the shortest phrase" );
+
+	ok( chewing_cand_list_first( ctx ) == 0,
+		"chewing_cand_list_first shall return 0" );
+	ok_candidate( ctx, CAND_1, ARRAY_SIZE( CAND_1 ) );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_cand_page()
+{
+	static const int DEFAULT_SELKEY[] = {
+		'1', '2', '3', '4', '5', '6', '7', '8', '9', '0',
+	};
+	int *sel_key;
+	int total_page;
+	int i;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	ok( chewing_cand_CurrentPage( ctx ) == -1,
+		"chewing_cand_CurrentPage shall return -1 when window is closed" );
+
+	type_keystoke_by_string( ctx, "hk4<D>" ); // ㄘㄜˋ
+
+	total_page = chewing_cand_TotalPage( ctx );
+	ok( total_page > 1, "ㄘㄜˋ shall have more than one page" );
+	ok( total_page == ( chewing_cand_TotalChoice( ctx ) +
+		chewing_cand_ChoicePerPage( ctx ) - 1 ) /
+		chewing_cand_ChoicePerPage( ctx ),
+		"chewing_cand_TotalPage shall match TotalChoice and ChoicePerPage" );
+	ok( chewing_cand_CurrentPage( ctx ) == 0,
+		"chewing_cand_CurrentPage shall start from 0" );
+
+	type_keystoke_by_string( ctx, "<PD>" );
+	ok( chewing_cand_CurrentPage( ctx ) == 1,
+		"chewing_cand_CurrentPage shall be 1 after page down" );
+
+	type_keystoke_by_string( ctx, "<PU><PU>" );
+	ok( chewing_cand_CurrentPage( ctx ) == total_page - 1,
+		"chewing_cand_CurrentPage shall wrap to the last page" );
+
+	sel_key = chewing_get_selKey( ctx );
+	ok( sel_key, "chewing_get_selKey shall not return NULL" );
+	for ( i = 0; i < chewing_cand_ChoicePerPage( ctx ); ++i ) {
+		ok( sel_key[ i ] == DEFAULT_SELKEY[ i ],
+			"selection key shall be the default one" );
+	}
+	chewing_free( sel_key );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_select_candidate_shortest_phrase_first()
+{
+	static const char *CAND_1[] = {
+		"一上來",
+		"移上來",
+	};
+
+	static const char *CAND_2[] = {
+		"移上",
+	};
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+	chewing_set_shortestPhraseFirst( ctx, 1 );
+
+	type_keystoke_by_string( ctx, "u6g;4x96<L><L><L>" ); // ㄧˊㄕㄤˋㄌㄞˊ
+
+	// ㄧˊ
+	type_keystoke_by_string( ctx, "<D>" );
+	chewing_cand_Enumerate( ctx );
+	ok( ueStrLen( chewing_cand_String_static( ctx ) ) == 1,
+		"the shortest phrase shall be listed first" );
+	ok( chewing_cand_list_has_prev( ctx ) == 0,
+		"there shall be no previous phrase" );
+
+	// ㄧˊㄕㄤˋ
+	type_keystoke_by_string( ctx, "<D>" );
+	ok_candidate( ctx, CAND_2, ARRAY_SIZE( CAND_2 ) );
+
+	// ㄧˊㄕㄤˋㄌㄞˊ
+	type_keystoke_by_string( ctx, "<D>" );
+	ok_candidate( ctx, CAND_1, ARRAY_SIZE( CAND_1 ) );
+	ok( chewing_cand_list_has_next( ctx ) == 0,
+		"there shall be no next phrase" );
+
+	ok( chewing_cand_list_prev( ctx ) == 0,
+		"chewing_cand_list_prev shall return 0" );
+	ok_candidate( ctx, CAND_2, ARRAY_SIZE( CAND_2 ) );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_auto_commit_phrase()
+{
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	// Four positions are reserved for bopomofo, so at most 3 characters can
+	// stay in preedit buffer.
+	chewing_set_maxChiSymbolLen( ctx, 8 );
+
+	type_keystoke_by_string( ctx, "hk4g4hk4" ); // ㄘㄜˋㄕˋㄘㄜˋ
+	ok_preedit_buffer( ctx, "測試測" );
+	ok( chewing_commit_Check( ctx ) == 0, "shall not commit" );
+
+	// The leading phrase 測試 is committed as a whole.
+	type_keystoke_by_string( ctx, "g4" ); // ㄕˋ
+	ok( chewing_commit_Check( ctx ) == 1, "shall commit" );
+	ok_commit_buffer( ctx, "測試" );
+	ok_preedit_buffer( ctx, "測試" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_auto_commit_selected_phrase()
+{
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 8 );
+
+	type_keystoke_by_string( ctx, "hk4g4<D>2hk4" ); // ㄘㄜˋㄕˋㄘㄜˋ
+	ok_preedit_buffer( ctx, "測是測" );
+
+	// 測 and 是 are not in the same phrase, so only 測 is committed.
+	type_keystoke_by_string( ctx, "g4" ); // ㄕˋ
+	ok_commit_buffer( ctx, "測" );
+	ok_preedit_buffer( ctx, "是測試" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_auto_commit_symbol()
+{
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 8 );
+
+	// The leading symbol is committed.
+	type_keystoke_by_string( ctx, "[hk4g4hk4" );
+	ok_commit_buffer( ctx, "「" );
+	ok_preedit_buffer( ctx, "測試測" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_KB_ET()
+{
+	ChewingContext *ctx;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+	chewing_set_KBType( ctx, chewing_KBStr2Num( "KB_ET" ) );
+	ok( chewing_get_KBType( ctx ) == chewing_KBStr2Num( "KB_ET" ),
+		"KBType shall be KB_ET" );
+
+	type_keystoke_by_string( ctx, "'r" );
+	ok_zuin_buffer( ctx, "ㄘㄜ" );
+	/* tones are placed on 1 (˙), 2, 3 and 4 */
+	type_keystoke_by_string( ctx, "4/4" );
+	ok_preedit_buffer( ctx, "測試" );
+	ok_zuin_buffer( ctx, "" );
+
+	type_keystoke_by_string( ctx, "dr1" );
+	ok_preedit_buffer( ctx, "測試的" );
+
+	/* ㄑ, ㄢ, ㄣ, ㄤ, ㄥ and ㄦ are on the number row */
+	type_keystoke_by_string( ctx, "7" );
+	ok_zuin_buffer( ctx, "ㄑ" );
+	type_keystoke_by_string( ctx, "<EE>=2" );
+	ok_preedit_buffer( ctx, "測試的而" );
+	type_keystoke_by_string( ctx, "k8" );
+	ok_zuin_buffer( ctx, "ㄎㄢ" );
+	type_keystoke_by_string( ctx, "4" );
+	ok_preedit_buffer( ctx, "測試的而看" );
+
+	/* space is the first tone */
+	type_keystoke_by_string( ctx, "<E>ge " );
+	ok_preedit_buffer( ctx, "機" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+/*
+ * Pinyin keys are packed into the bopomofo buffer, so only the string is
+ * compared.
+ */
+static void ok_pinyin_buffer( ChewingContext *ctx, const char *expected )
+{
+	char *buf = chewing_zuin_String( ctx, NULL );
+	ok( !strcmp( buf, expected ), "pinyin buffer `%s' shall be `%s'",
+		buf, expected );
+	chewing_free( buf );
+}
+
+void test_KB_HANYU_PINYIN_backspace()
+{
+	ChewingContext *ctx;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+	chewing_set_KBType( ctx, chewing_KBStr2Num( "KB_HANYU_PINYIN" ) );
+
+	/* backspace removes the last key of the pinyin sequence */
+	type_keystoke_by_string( ctx, "shi" );
+	ok_pinyin_buffer( ctx, "shi" );
+	type_keystoke_by_string( ctx, "<B>" );
+	ok_pinyin_buffer( ctx, "sh" );
+	type_keystoke_by_string( ctx, "<B><B>" );
+	ok_pinyin_buffer( ctx, "" );
+	ok_preedit_buffer( ctx, "" );
+
+	/* the sequence can be completed after editing */
+	type_keystoke_by_string( ctx, "cee<B>4" );
+	ok_preedit_buffer( ctx, "測" );
+	ok_pinyin_buffer( ctx, "" );
+
+	/* backspace without sequence removes the character */
+	type_keystoke_by_string( ctx, "shi4<B>" );
+	ok_preedit_buffer( ctx, "測" );
+	type_keystoke_by_string( ctx, "s<B><B>" );
+	ok_preedit_buffer( ctx, "" );
+	ok_pinyin_buffer( ctx, "" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_bopomofo_buffer()
+{
+	ChewingContext *ctx;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	ok( chewing_bopomofo_Check( ctx ) == 0,
+		"bopomofo buffer shall be empty" );
+	ok( strcmp( chewing_bopomofo_String_static( ctx ), "" ) == 0,
+		"bopomofo string shall be empty" );
+
+	type_keystoke_by_string( ctx, "hk" );
+	ok( chewing_bopomofo_Check( ctx ) == 1,
+		"bopomofo buffer shall not be empty" );
+	ok( strcmp( chewing_bopomofo_String_static( ctx ), "ㄘㄜ" ) == 0,
+		"bopomofo string shall be ㄘㄜ" );
+
+	type_keystoke_by_string( ctx, "4" );
+	ok( chewing_bopomofo_Check( ctx ) == 0,
+		"bopomofo buffer shall be empty" );
+	ok( strcmp( chewing_bopomofo_String_static( ctx ), "" ) == 0,
+		"bopomofo string shall be empty" );
+	ok_preedit_buffer( ctx, "測" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_phone_correction()
+{
+	ChewingContext *ctx;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	/* ㄘㄜˇ has no word */
+	type_keystoke_by_string( ctx, "hk3" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "keystroke shall ring the bell" );
+	ok_preedit_buffer( ctx, "" );
+
+	chewing_set_phoneCorrection( ctx, 1 );
+
+	/* another tone */
+	type_keystoke_by_string( ctx, "hk3" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 0, "keystroke shall not ring the bell" );
+	ok_preedit_buffer( ctx, "測" );
+	ok_aux_buffer( ctx, "修正：ㄘㄜˋ" );
+	chewing_clean_preedit_buf( ctx );
+
+	/* ㄅ (1) is next to ㄉ (2) */
+	type_keystoke_by_string( ctx, "1k7" );
+	ok_preedit_buffer( ctx, "的" );
+	ok_aux_buffer( ctx, "修正：ㄉㄜ˙" );
+	chewing_clean_preedit_buf( ctx );
+
+	chewing_set_KBType( ctx, chewing_KBStr2Num( "KB_DVORAK" ) );
+
+	/* ㄇ (a) is next to ㄋ (o) on the Dvorak keyboard */
+	type_keystoke_by_string( ctx, "am3" );
+	ok_preedit_buffer( ctx, "女" );
+	ok_aux_buffer( ctx, "修正：ㄋㄩˇ" );
+	chewing_clean_preedit_buf( ctx );
+
+	/* ㄦ ([) is next to ㄢ (0) on the Dvorak keyboard */
+	type_keystoke_by_string( ctx, "1[ " );
+	ok_preedit_buffer( ctx, "搬" );
+	ok_aux_buffer( ctx, "修正：ㄅㄢ" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_KB_layout()
+{
+	/* 測試 (ㄘㄜˋ ㄕˋ) typed in every keyboard layout */
+	static const struct {
+		const char *kbtype;
+		const char *keystroke;
+		const char *expected;
+	} LAYOUT_DATA[] = {
+		{ "KB_DEFAULT", "hk4g4", "測試" },
+		{ "KB_HSU", "agjcj", "測試" },
+		{ "KB_IBM", "oh.y.", "測試" },
+		{ "KB_GIN_YIEH", "jozhz", "測試" },
+		{ "KB_ET", "'r4/4", "測試" },
+		{ "KB_ET26", "wrkck", "測試" },
+		{ "KB_DVORAK", "dt4i4", "測試" },
+		{ "KB_DVORAK_HSU", "aucic", "測試" },
+		{ "KB_DACHEN_CP26", "hkdgd", "測試" },
+		{ "KB_HANYU_PINYIN", "ce4shi4", "測試" },
+	};
+	ChewingContext *ctx;
+	size_t i;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	for ( i = 0; i < ARRAY_SIZE( LAYOUT_DATA ); ++i ) {
+		chewing_set_KBType( ctx, chewing_KBStr2Num( LAYOUT_DATA[ i ].kbtype ) );
+		type_keystoke_by_string( ctx, LAYOUT_DATA[ i ].keystroke );
+		ok_preedit_buffer( ctx, LAYOUT_DATA[ i ].expected );
+		chewing_Reset( ctx );
+	}
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
 int main()
 {
 	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
@@ -117,6 +628,20 @@ int main()
 
 	test_select_candidate();
 	test_select_candidate_phrase_choice_rearward();
+	test_phrase_with_three_syllables();
+	test_cand_open_close();
+	test_cand_list();
+	test_cand_page();
+	test_select_candidate_shortest_phrase_first();
+	test_auto_commit_phrase();
+	test_auto_commit_selected_phrase();
+	test_auto_commit_symbol();
+
+	test_KB_ET();
+	test_KB_HANYU_PINYIN_backspace();
+	test_KB_layout();
+	test_bopomofo_buffer();
+	test_phone_correction();
 
 	return exit_status();
 }
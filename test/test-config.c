@@ -22,9 +22,15 @@ static const int MIN_CAND_PER_PAGE = 1;
 static const int MAX_CAND_PER_PAGE = 10;
 static const int DEFAULT_CAND_PER_PAGE = 10;
 
+static const int MIN_CHI_SYMBOL_LEN = 0;
+static const int MAX_CHI_SYMBOL_LEN = 40;
+
 static const int DEFAULT_SELECT_KEY[] = {
 	'1', '2', '3', '4', '5', '6', '7', '8', '9', '0' };
 
+static const int ALTERNATE_SELECT_KEY[] = {
+	'a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l', ';' };
+
 void test_default_select_key()
 {
 	chewing_Init( 0, 0 );
@@ -65,6 +71,389 @@ void test_cand_per_page()
 	chewing_Terminate();
 }
 
+void test_numpad_as_selection()
+{
+	chewing_Init( 0, 0 );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	ok( chewing_get_numpadAsSelection( ctx ) == 1,
+		"numpadAsSelection shall be default value" );
+
+	chewing_set_numpadAsSelection( ctx, 0 );
+	ok( chewing_get_numpadAsSelection( ctx ) == 0,
+		"numpadAsSelection shall be 0" );
+
+	chewing_Reset( ctx );
+	ok( chewing_get_numpadAsSelection( ctx ) == 0,
+		"numpadAsSelection shall not be reset by chewing_Reset" );
+
+	chewing_set_numpadAsSelection( ctx, 2 );
+	ok( chewing_get_numpadAsSelection( ctx ) == 1,
+		"numpadAsSelection shall be 1" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_set_select_key()
+{
+	int *select_key;
+
+	chewing_Init( 0, 0 );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_selKey( ctx, (int *) ALTERNATE_SELECT_KEY, 0 );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, DEFAULT_SELECT_KEY,
+		sizeof( DEFAULT_SELECT_KEY )),
+		"select key shall not change" );
+	chewing_free( select_key );
+
+	chewing_set_selKey( ctx, (int *) ALTERNATE_SELECT_KEY,
+		ARRAY_SIZE( ALTERNATE_SELECT_KEY ) + 1 );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, DEFAULT_SELECT_KEY,
+		sizeof( DEFAULT_SELECT_KEY )),
+		"select key shall not change" );
+	chewing_free( select_key );
+
+	chewing_set_selKey( ctx, (int *) ALTERNATE_SELECT_KEY,
+		ARRAY_SIZE( ALTERNATE_SELECT_KEY ) );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, ALTERNATE_SELECT_KEY,
+		sizeof( ALTERNATE_SELECT_KEY )),
+		"select key shall be alternate value" );
+	chewing_free( select_key );
+
+	chewing_set_selKey( ctx, (int *) DEFAULT_SELECT_KEY, 3 );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, DEFAULT_SELECT_KEY, sizeof( select_key[0] ) * 3 ),
+		"the first 3 select keys shall be set" );
+	ok( select_key[ 3 ] == 0, "the rest select keys shall be cleared" );
+	chewing_free( select_key );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_max_chi_symbol_len()
+{
+	chewing_Init( 0, 0 );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+	ok( chewing_get_maxChiSymbolLen( ctx ) == 16,
+		"maxChiSymbolLen shall be 16" );
+
+	chewing_set_maxChiSymbolLen( ctx, MIN_CHI_SYMBOL_LEN - 1 );
+	ok( chewing_get_maxChiSymbolLen( ctx ) == 16,
+		"maxChiSymbolLen shall not change" );
+
+	chewing_set_maxChiSymbolLen( ctx, MAX_CHI_SYMBOL_LEN + 1 );
+	ok( chewing_get_maxChiSymbolLen( ctx ) == 16,
+		"maxChiSymbolLen shall not change" );
+
+	chewing_set_maxChiSymbolLen( ctx, MAX_CHI_SYMBOL_LEN );
+	ok( chewing_get_maxChiSymbolLen( ctx ) == MAX_CHI_SYMBOL_LEN,
+		"maxChiSymbolLen shall be MAX_CHI_SYMBOL_LEN" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_hsu_select_key_type()
+{
+	static const int HSU_SELKEY_1[] = {
+		'a', 's', 'd', 'f', 'j', 'k', 'l', '7', '8', '9' };
+	static const int HSU_SELKEY_2[] = {
+		'a', 's', 'd', 'f', 'z', 'x', 'c', 'v', '8', '9' };
+	static const int CUSTOM_SELKEY[] = {
+		'q', 'w', 'e', 'r', 't', 'y', 'u', 'i', 'o', 'p' };
+	int *select_key;
+
+	chewing_Init( 0, 0 );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_selKey( ctx, (int *) CUSTOM_SELKEY, ARRAY_SIZE( CUSTOM_SELKEY ) );
+	chewing_set_hsuSelKeyType( ctx, HSU_SELKEY_TYPE1 );
+	ok( chewing_get_hsuSelKeyType( ctx ) == HSU_SELKEY_TYPE1,
+		"hsuSelKeyType shall be HSU_SELKEY_TYPE1" );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, CUSTOM_SELKEY, sizeof( CUSTOM_SELKEY ) ),
+		"select key shall not change in KB_DEFAULT" );
+	chewing_free( select_key );
+
+	chewing_set_KBType( ctx, KB_HSU );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, HSU_SELKEY_1, sizeof( HSU_SELKEY_1 ) ),
+		"select key shall be asdfjkl789 after switching to KB_HSU" );
+	chewing_free( select_key );
+
+	chewing_set_selKey( ctx, (int *) CUSTOM_SELKEY, ARRAY_SIZE( CUSTOM_SELKEY ) );
+	chewing_set_hsuSelKeyType( ctx, HSU_SELKEY_TYPE1 );
+	ok( chewing_get_hsuSelKeyType( ctx ) == HSU_SELKEY_TYPE1,
+		"hsuSelKeyType shall be HSU_SELKEY_TYPE1" );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, HSU_SELKEY_1, sizeof( HSU_SELKEY_1 ) ),
+		"select key shall be asdfjkl789" );
+	chewing_free( select_key );
+
+	chewing_set_hsuSelKeyType( ctx, HSU_SELKEY_TYPE2 );
+	ok( chewing_get_hsuSelKeyType( ctx ) == HSU_SELKEY_TYPE2,
+		"hsuSelKeyType shall be HSU_SELKEY_TYPE2" );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, HSU_SELKEY_2, sizeof( HSU_SELKEY_2 ) ),
+		"select key shall be asdfzxcv89" );
+	chewing_free( select_key );
+
+	chewing_set_hsuSelKeyType( ctx, HSU_SELKEY_TYPE2 + 1 );
+	ok( chewing_get_hsuSelKeyType( ctx ) == HSU_SELKEY_TYPE2,
+		"hsuSelKeyType shall not change" );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, HSU_SELKEY_2, sizeof( HSU_SELKEY_2 ) ),
+		"select key shall not change" );
+	chewing_free( select_key );
+
+	chewing_set_KBType( ctx, KB_ET );
+	chewing_set_selKey( ctx, (int *) CUSTOM_SELKEY, ARRAY_SIZE( CUSTOM_SELKEY ) );
+	chewing_set_hsuSelKeyType( ctx, HSU_SELKEY_TYPE1 );
+	select_key = chewing_get_selKey( ctx );
+	ok( !memcmp( select_key, CUSTOM_SELKEY, sizeof( CUSTOM_SELKEY ) ),
+		"select key shall not change in KB_ET" );
+	chewing_free( select_key );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_boolean_config()
+{
+	chewing_Init( 0, 0 );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_autoShiftCur( ctx, 2 );
+	ok( chewing_get_autoShiftCur( ctx ) == 1, "autoShiftCur shall be 1" );
+	chewing_set_autoShiftCur( ctx, 0 );
+	ok( chewing_get_autoShiftCur( ctx ) == 0, "autoShiftCur shall be 0" );
+
+	chewing_set_easySymbolInput( ctx, 2 );
+	ok( chewing_get_easySymbolInput( ctx ) == 1, "easySymbolInput shall be 1" );
+	chewing_set_easySymbolInput( ctx, 0 );
+	ok( chewing_get_easySymbolInput( ctx ) == 0, "easySymbolInput shall be 0" );
+
+	chewing_set_phraseChoiceRearward( ctx, 2 );
+	ok( chewing_get_phraseChoiceRearward( ctx ) == 1,
+		"phraseChoiceRearward shall be 1" );
+	chewing_set_phraseChoiceRearward( ctx, 0 );
+	ok( chewing_get_phraseChoiceRearward( ctx ) == 0,
+		"phraseChoiceRearward shall be 0" );
+
+	ok( chewing_get_autoLearn( ctx ) == AUTOLEARN_ENABLED,
+		"autoLearn shall be AUTOLEARN_ENABLED by default" );
+	chewing_set_autoLearn( ctx, AUTOLEARN_DISABLED );
+	ok( chewing_get_autoLearn( ctx ) == AUTOLEARN_DISABLED,
+		"autoLearn shall be AUTOLEARN_DISABLED" );
+	chewing_set_autoLearn( ctx, 2 );
+	ok( chewing_get_autoLearn( ctx ) == AUTOLEARN_DISABLED,
+		"autoLearn shall not change" );
+
+	ok( chewing_get_recentPhraseFirst( ctx ) == 0,
+		"recentPhraseFirst shall be 0 by default" );
+	chewing_set_recentPhraseFirst( ctx, 2 );
+	ok( chewing_get_recentPhraseFirst( ctx ) == 1,
+		"recentPhraseFirst shall be 1" );
+	chewing_set_recentPhraseFirst( ctx, 0 );
+	ok( chewing_get_recentPhraseFirst( ctx ) == 0,
+		"recentPhraseFirst shall be 0" );
+
+	ok( chewing_get_autoPairSymbol( ctx ) == 0,
+		"autoPairSymbol shall be 0 by default" );
+	chewing_set_autoPairSymbol( ctx, 2 );
+	ok( chewing_get_autoPairSymbol( ctx ) == 1,
+		"autoPairSymbol shall be 1" );
+	chewing_set_autoPairSymbol( ctx, 0 );
+	ok( chewing_get_autoPairSymbol( ctx ) == 0,
+		"autoPairSymbol shall be 0" );
+
+	ok( chewing_get_shortestPhraseFirst( ctx ) == 0,
+		"shortestPhraseFirst shall be 0 by default" );
+	chewing_set_shortestPhraseFirst( ctx, 2 );
+	ok( chewing_get_shortestPhraseFirst( ctx ) == 1,
+		"shortestPhraseFirst shall be 1" );
+	chewing_set_shortestPhraseFirst( ctx, 0 );
+	ok( chewing_get_shortestPhraseFirst( ctx ) == 0,
+		"shortestPhraseFirst shall be 0" );
+
+	ok( chewing_get_phoneCorrection( ctx ) == 0,
+		"phoneCorrection shall be 0 by default" );
+	chewing_set_phoneCorrection( ctx, 2 );
+	ok( chewing_get_phoneCorrection( ctx ) == 1,
+		"phoneCorrection shall be 1" );
+	chewing_set_phoneCorrection( ctx, 0 );
+	ok( chewing_get_phoneCorrection( ctx ) == 0,
+		"phoneCorrection shall be 0" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_auto_learn_suppression()
+{
+	chewing_Init( 0, 0 );
+
+	ChewingContext *ctx = chewing_new();
+
+	ok( chewing_get_autoLearnSuppression( ctx, "測試" ) == 0,
+		"測試 shall not be suppressed" );
+	ok( chewing_set_autoLearnSuppression( ctx, "測試", 1 ) == 0,
+		"chewing_set_autoLearnSuppression shall succeed" );
+	ok( chewing_get_autoLearnSuppression( ctx, "測試" ) == 1,
+		"測試 shall be suppressed" );
+	ok( chewing_set_autoLearnSuppression( ctx, "測試", 1 ) == 0,
+		"adding the same phrase twice shall succeed" );
+	ok( chewing_set_autoLearnSuppression( ctx, "", 1 ) == -1,
+		"empty phrase shall be rejected" );
+	ok( chewing_set_autoLearnSuppression( ctx, NULL, 1 ) == -1,
+		"NULL phrase shall be rejected" );
+
+	chewing_Reset( ctx );
+	ok( chewing_get_autoLearnSuppression( ctx, "測試" ) == 1,
+		"測試 shall be suppressed after reset" );
+
+	chewing_set_autoLearnSuppression( ctx, "測試", 0 );
+	ok( chewing_get_autoLearnSuppression( ctx, "測試" ) == 0,
+		"測試 shall not be suppressed" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_KBType()
+{
+	char *name;
+	char *kb_string;
+	int i, total;
+
+	chewing_Init( 0, 0 );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	total = chewing_kbtype_Total( ctx );
+	ok( total > 0, "chewing_kbtype_Total shall return positive number" );
+
+	i = 0;
+	chewing_kbtype_Enumerate( ctx );
+	while ( chewing_kbtype_hasNext( ctx ) ) {
+		name = chewing_kbtype_String( ctx );
+		ok( chewing_KBStr2Num( name ) == i,
+			"chewing_KBStr2Num( \"%s\" ) shall be %d", name, i );
+
+		chewing_set_KBType( ctx, i );
+		ok( chewing_get_KBType( ctx ) == i, "KBType shall be %d", i );
+		kb_string = chewing_get_KBString( ctx );
+		ok( ! strcmp( kb_string, name ), "KBString shall be %s", name );
+		chewing_free( kb_string );
+
+		chewing_free( name );
+		++i;
+	}
+	ok( i == total, "kbtype enumeration shall return %d names", total );
+
+	i = 0;
+	chewing_kbtype_Enumerate( ctx );
+	while ( chewing_kbtype_hasNext( ctx ) ) {
+		const char *static_name = chewing_kbtype_String_static( ctx );
+		ok( chewing_KBStr2Num( static_name ) == i,
+			"chewing_KBStr2Num( \"%s\" ) shall be %d", static_name, i );
+		++i;
+	}
+	ok( i == total, "kbtype enumeration shall return %d names", total );
+	ok( strcmp( chewing_kbtype_String_static( ctx ), "" ) == 0,
+		"chewing_kbtype_String_static shall return empty string after enumeration" );
+
+	ok( chewing_KBStr2Num( "KB_NO_SUCH_LAYOUT" ) ==
+		chewing_KBStr2Num( "KB_DEFAULT" ),
+		"unknown name shall map to KB_DEFAULT" );
+
+	chewing_set_KBType( ctx, total );
+	ok( chewing_get_KBType( ctx ) == chewing_KBStr2Num( "KB_DEFAULT" ),
+		"invalid KBType shall fall back to KB_DEFAULT" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_KB_enum()
+{
+	static const struct {
+		int kbtype;
+		const char *name;
+	} KB_NAMES[] = {
+		{ KB_DEFAULT, "KB_DEFAULT" },
+		{ KB_HSU, "KB_HSU" },
+		{ KB_IBM, "KB_IBM" },
+		{ KB_GIN_YIEH, "KB_GIN_YIEH" },
+		{ KB_ET, "KB_ET" },
+		{ KB_ET26, "KB_ET26" },
+		{ KB_DVORAK, "KB_DVORAK" },
+		{ KB_DVORAK_HSU, "KB_DVORAK_HSU" },
+		{ KB_DACHEN_CP26, "KB_DACHEN_CP26" },
+		{ KB_HANYU_PINYIN, "KB_HANYU_PINYIN" },
+	};
+	size_t i;
+
+	ok( ARRAY_SIZE( KB_NAMES ) == KB_TYPE_NUM,
+		"KB_TYPE_NUM shall be %d", (int) ARRAY_SIZE( KB_NAMES ) );
+	for ( i = 0; i < ARRAY_SIZE( KB_NAMES ); ++i ) {
+		ok( chewing_KBStr2Num( KB_NAMES[ i ].name ) == KB_NAMES[ i ].kbtype,
+			"chewing_KBStr2Num( \"%s\" ) shall be %d",
+			KB_NAMES[ i ].name, KB_NAMES[ i ].kbtype );
+	}
+}
+
+void test_null_context()
+{
+	ok( chewing_Reset( NULL ) == -1, "chewing_Reset shall return -1" );
+	ok( chewing_set_KBType( NULL, 0 ) == -1,
+		"chewing_set_KBType shall return -1" );
+	ok( chewing_get_KBType( NULL ) == -1,
+		"chewing_get_KBType shall return -1" );
+	ok( chewing_get_KBString( NULL ) == NULL,
+		"chewing_get_KBString shall return NULL" );
+	ok( chewing_get_selKey( NULL ) == NULL,
+		"chewing_get_selKey shall return NULL" );
+	ok( chewing_handle_Default( NULL, 'a' ) == -1,
+		"chewing_handle_Default shall return -1" );
+	ok( chewing_handle_Enter( NULL ) == -1,
+		"chewing_handle_Enter shall return -1" );
+	ok( chewing_commit_String( NULL ) == NULL,
+		"chewing_commit_String shall return NULL" );
+	ok( chewing_buffer_Len( NULL ) == -1,
+		"chewing_buffer_Len shall return -1" );
+	ok( chewing_cand_hasNext( NULL ) == 0,
+		"chewing_cand_hasNext shall return 0" );
+	ok( chewing_userphrase_has_next( NULL, NULL, NULL ) == 0,
+		"chewing_userphrase_has_next shall return 0" );
+	ok( chewing_userphrase_add( NULL, "測試", "ㄘㄜˋ ㄕˋ" ) == -1,
+		"chewing_userphrase_add shall return -1" );
+
+	/* shall not crash */
+	chewing_set_candPerPage( NULL, 10 );
+	chewing_cand_Enumerate( NULL );
+	chewing_delete( NULL );
+}
+
 int main()
 {
 	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
@@ -72,6 +461,15 @@ int main()
 
 	test_default_select_key();
 	test_cand_per_page();
+	test_numpad_as_selection();
+	test_set_select_key();
+	test_max_chi_symbol_len();
+	test_hsu_select_key_type();
+	test_boolean_config();
+	test_auto_learn_suppression();
+	test_KBType();
+	test_KB_enum();
+	test_null_context();
 
 	return exit_status();
 }
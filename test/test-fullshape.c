@@ -148,6 +148,36 @@ void test_set_fullshape()
 	chewing_Terminate();
 }
 
+void test_shift_space()
+{
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	type_keystoke_by_string( ctx, "<SS>" );
+	ok( chewing_get_ShapeMode( ctx ) == FULLSHAPE_MODE,
+		"mode shall change to FULLSHAPE_MODE" );
+
+	chewing_set_ChiEngMode( ctx, SYMBOL_MODE );
+	type_keystoke_by_string( ctx, " " );
+	ok_commit_buffer( ctx, "\xE3\x80\x80" ); /* Full width space */
+
+	type_keystoke_by_string( ctx, "<SS>" );
+	ok( chewing_get_ShapeMode( ctx ) == HALFSHAPE_MODE,
+		"mode shall change to HALFSHAPE_MODE" );
+
+	chewing_set_ChiEngMode( ctx, CHINESE_MODE );
+	type_keystoke_by_string( ctx, "hk4<D><SS>" );
+	ok( chewing_get_ShapeMode( ctx ) == HALFSHAPE_MODE,
+		"mode shall not change when candidate window is open" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
 int main()
 {
 	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
@@ -155,6 +185,7 @@ int main()
 
 	test_set_fullshape();
 	test_fullshape_input();
+	test_shift_space();
 
 	return exit_status();
 }
@@ -0,0 +1,166 @@
+/**
+ * test-interval.c
+ *
+ * Copyright (c) 2012
+ *	libchewing Core Team. See ChangeLog for details.
+ *
+ * See the file "COPYING" for information on usage and redistribution
+ * of this file.
+ */
+
+#ifdef HAVE_CONFIG_H
+#include <config.h>
+#endif
+
+#include <stdlib.h>
+#include <stdio.h>
+
+#include "chewing.h"
+#include "plat_types.h"
+#include "hash-private.h"
+#include "test.h"
+
+static void ok_interval( ChewingContext *ctx, const IntervalType *expected,
+	size_t expected_len )
+{
+	IntervalType it;
+	size_t i;
+
+	chewing_interval_Enumerate( ctx );
+	for ( i = 0; i < expected_len; ++i ) {
+		ok( chewing_interval_hasNext( ctx ), "shall have next interval" );
+		chewing_interval_Get( ctx, &it );
+		ok( it.from == expected[ i ].from && it.to == expected[ i ].to,
+			"interval `[%d, %d)' shall be `[%d, %d)'",
+			it.from, it.to, expected[ i ].from, expected[ i ].to );
+	}
+	ok( !chewing_interval_hasNext( ctx ), "shall not have next interval" );
+}
+
+void test_interval()
+{
+	static const IntervalType ONE_PHRASE[] = {
+		{ .from = 0, .to = 2 },
+	};
+	static const IntervalType TWO_PHRASES[] = {
+		{ .from = 0, .to = 2 },
+		{ .from = 2, .to = 4 },
+	};
+	static const IntervalType BREAK_POINT[] = {
+		{ .from = 0, .to = 1 },
+		{ .from = 1, .to = 2 },
+	};
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	ok_interval( ctx, NULL, 0 );
+
+	type_keystoke_by_string( ctx, "hk4g4" ); // 測試
+	ok_interval( ctx, ONE_PHRASE, ARRAY_SIZE( ONE_PHRASE ) );
+
+	type_keystoke_by_string( ctx, "hk4g4" ); // 測試測試
+	ok_interval( ctx, TWO_PHRASES, ARRAY_SIZE( TWO_PHRASES ) );
+
+	type_keystoke_by_string( ctx, "<EE><B><B>" ); // 測試
+	type_keystoke_by_string( ctx, "<L><T>" ); // 測|試
+	ok_interval( ctx, BREAK_POINT, ARRAY_SIZE( BREAK_POINT ) );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_interval_with_symbol()
+{
+	static const IntervalType EXPECTED[] = {
+		{ .from = 0, .to = 2 },
+		{ .from = 3, .to = 5 },
+	};
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	// The interval shall be counted in characters of preedit buffer,
+	// including the symbol.
+	type_keystoke_by_string( ctx, "hk4g4[hk4g4" );
+	ok_preedit_buffer( ctx, "測試「測試" );
+	ok_interval( ctx, EXPECTED, ARRAY_SIZE( EXPECTED ) );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_buffer_offset()
+{
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	ok( chewing_buffer_CharToByte( ctx, 0 ) == 0,
+		"offset of empty buffer shall be 0" );
+	ok( chewing_buffer_PhoneToChar( ctx, 0 ) == 0,
+		"offset of empty buffer shall be 0" );
+
+	// 3 bytes for each character, and a 1-byte symbol in the middle.
+	type_keystoke_by_string( ctx, "hk4g4" );
+	chewing_set_ChiEngMode( ctx, SYMBOL_MODE );
+	type_keystoke_by_string( ctx, "a" );
+	chewing_set_ChiEngMode( ctx, CHINESE_MODE );
+	type_keystoke_by_string( ctx, "hk4g4" );
+	ok_preedit_buffer( ctx, "測試a測試" );
+
+	ok( chewing_buffer_CharToByte( ctx, 2 ) == 6, "byte offset shall be 6" );
+	ok( chewing_buffer_CharToByte( ctx, 3 ) == 7, "byte offset shall be 7" );
+	ok( chewing_buffer_CharToByte( ctx, 5 ) == 13, "byte offset shall be 13" );
+	ok( chewing_buffer_CharToByte( ctx, 6 ) == -1,
+		"out of range offset shall return -1" );
+	ok( chewing_buffer_CharToByte( ctx, -1 ) == -1,
+		"out of range offset shall return -1" );
+
+	ok( chewing_buffer_ByteToChar( ctx, 7 ) == 3, "char offset shall be 3" );
+	ok( chewing_buffer_ByteToChar( ctx, 13 ) == 5, "char offset shall be 5" );
+	ok( chewing_buffer_ByteToChar( ctx, 8 ) == -1,
+		"offset inside a character shall return -1" );
+	ok( chewing_buffer_ByteToChar( ctx, 14 ) == -1,
+		"out of range offset shall return -1" );
+
+	ok( chewing_buffer_PhoneToChar( ctx, 1 ) == 1, "char offset shall be 1" );
+	ok( chewing_buffer_PhoneToChar( ctx, 2 ) == 3,
+		"symbol shall be skipped" );
+	ok( chewing_buffer_PhoneToChar( ctx, 4 ) == 5,
+		"end of syllables shall be the end of buffer" );
+	ok( chewing_buffer_PhoneToChar( ctx, 5 ) == -1,
+		"out of range index shall return -1" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+int main()
+{
+	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
+	putenv( "CHEWING_USER_PATH=" TEST_HASH_DIR );
+
+	test_interval();
+	test_interval_with_symbol();
+	test_buffer_offset();
+
+	return exit_status();
+}
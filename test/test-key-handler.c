@@ -0,0 +1,699 @@
+/**
+ * test-key-handler.c
+ *
+ * Copyright (c) 2012
+ *	libchewing Core Team. See ChangeLog for details.
+ *
+ * See the file "COPYING" for information on usage and redistribution
+ * of this file.
+ */
+
+#ifdef HAVE_CONFIG_H
+#include <config.h>
+#endif
+
+#include <stdlib.h>
+#include <stdio.h>
+
+#include "chewing.h"
+#include "plat_types.h"
+#include "hash-private.h"
+#include "test.h"
+
+static ChewingContext *create_context()
+{
+	ChewingContext *ctx;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ctx = chewing_new();
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+	return ctx;
+}
+
+static void destroy_context( ChewingContext *ctx )
+{
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_Backspace_remove_bopomofo()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk" );
+	ok_zuin_buffer( ctx, "ㄘㄜ" );
+
+	type_keystoke_by_string( ctx, "<B>" );
+	ok_zuin_buffer( ctx, "ㄘ" );
+	ok_preedit_buffer( ctx, "" );
+
+	type_keystoke_by_string( ctx, "<B>" );
+	ok_zuin_buffer( ctx, "" );
+	ok_preedit_buffer( ctx, "" );
+
+	destroy_context( ctx );
+}
+
+void test_Backspace_remove_bopomofo_before_char()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g" );
+	ok_preedit_buffer( ctx, "測" );
+	ok_zuin_buffer( ctx, "ㄕ" );
+
+	type_keystoke_by_string( ctx, "<B>" );
+	ok_preedit_buffer( ctx, "測" );
+	ok_zuin_buffer( ctx, "" );
+
+	type_keystoke_by_string( ctx, "<B>" );
+	ok_preedit_buffer( ctx, "" );
+	ok_zuin_buffer( ctx, "" );
+
+	destroy_context( ctx );
+}
+
+void test_Backspace_remove_char_before_cursor()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<L><B>" );
+	ok_preedit_buffer( ctx, "是" );
+	ok( chewing_cursor_Current( ctx ) == 0, "cursor shall be 0" );
+
+	type_keystoke_by_string( ctx, "<B>" );
+	ok_preedit_buffer( ctx, "是" );
+	ok( chewing_cursor_Current( ctx ) == 0, "cursor shall be 0" );
+
+	destroy_context( ctx );
+}
+
+void test_Backspace_remove_symbol()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4[" );
+	ok_preedit_buffer( ctx, "測「" );
+
+	type_keystoke_by_string( ctx, "<B>" );
+	ok_preedit_buffer( ctx, "測" );
+	ok( chewing_cursor_Current( ctx ) == 1, "cursor shall be 1" );
+
+	destroy_context( ctx );
+}
+
+void test_Backspace_remove_selected_interval()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<H><D>2" );
+	ok_preedit_buffer( ctx, "側室" );
+
+	/* Removing a character inside a selection shall drop the selection */
+	type_keystoke_by_string( ctx, "<EN><B>" );
+	ok_preedit_buffer( ctx, "測" );
+
+	destroy_context( ctx );
+}
+
+void test_Backspace_in_select()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<D>" );
+	ok( chewing_cand_TotalChoice( ctx ) > 0, "candidate window shall be open" );
+
+	type_keystoke_by_string( ctx, "<B>" );
+	ok_preedit_buffer( ctx, "測試" );
+
+	destroy_context( ctx );
+}
+
+void test_Backspace_empty_buffer()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "<B>" );
+	ok( chewing_keystroke_CheckIgnore( ctx ) == 1,
+		"Backspace on empty buffer shall be ignored" );
+	ok( chewing_keystroke_CheckAbsorb( ctx ) == 0,
+		"Backspace on empty buffer shall not be absorbed" );
+
+	destroy_context( ctx );
+}
+
+void test_Del_remove_char_after_cursor()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<H><DC>" );
+	ok_preedit_buffer( ctx, "是" );
+	ok( chewing_cursor_Current( ctx ) == 0, "cursor shall be 0" );
+
+	type_keystoke_by_string( ctx, "<DC>" );
+	ok_preedit_buffer( ctx, "" );
+	ok( chewing_cursor_Current( ctx ) == 0, "cursor shall be 0" );
+
+	destroy_context( ctx );
+}
+
+void test_Del_at_end_of_buffer()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<DC>" );
+	ok_preedit_buffer( ctx, "測試" );
+	ok( chewing_cursor_Current( ctx ) == 2, "cursor shall be 2" );
+
+	destroy_context( ctx );
+}
+
+void test_Del_when_bopomofo_entering()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4<H>g<DC>" );
+	ok_preedit_buffer( ctx, "測" );
+	ok_zuin_buffer( ctx, "ㄕ" );
+
+	destroy_context( ctx );
+}
+
+void test_Del_keep_shifted_selected_interval()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4hk4g4<H><R><D>2" );
+	ok_preedit_buffer( ctx, "測側室" );
+
+	type_keystoke_by_string( ctx, "<H><DC>" );
+	ok_preedit_buffer( ctx, "側室" );
+
+	destroy_context( ctx );
+}
+
+void test_Del_keep_shifted_breakpoint()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4hk4g4<L><T>" );
+	ok_preedit_buffer( ctx, "測測是" );
+
+	type_keystoke_by_string( ctx, "<H><DC>" );
+	ok_preedit_buffer( ctx, "測是" );
+
+	destroy_context( ctx );
+}
+
+void test_Del_keep_shifted_symbol()
+{
+	static const char *CAND[] = {
+		"「",
+		"『",
+		"《",
+		"〈",
+		"【",
+		"〔",
+	};
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4[<H><DC>" );
+	ok_preedit_buffer( ctx, "「" );
+
+	/* The symbol key shall move along with the symbol */
+	type_keystoke_by_string( ctx, "<D>" );
+	ok_candidate( ctx, CAND, ARRAY_SIZE( CAND ) );
+
+	destroy_context( ctx );
+}
+
+void test_Home_End()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<H>" );
+	ok( chewing_cursor_Current( ctx ) == 0, "cursor shall be 0" );
+
+	type_keystoke_by_string( ctx, "<EN>" );
+	ok( chewing_cursor_Current( ctx ) == 2, "cursor shall be 2" );
+
+	destroy_context( ctx );
+}
+
+void test_PageUp_PageDown_not_in_select()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<H><PU>" );
+	ok( chewing_cursor_Current( ctx ) == 2, "cursor shall be 2" );
+
+	type_keystoke_by_string( ctx, "<H><PD>" );
+	ok( chewing_cursor_Current( ctx ) == 2, "cursor shall be 2" );
+
+	destroy_context( ctx );
+}
+
+void test_PageUp_PageDown_in_select()
+{
+	int total_page;
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<L><D>" );
+	total_page = chewing_cand_TotalPage( ctx );
+	ok( total_page > 1, "total page shall be greater than 1" );
+	ok( chewing_cand_CurrentPage( ctx ) == 0, "current page shall be 0" );
+
+	type_keystoke_by_string( ctx, "<PD>" );
+	ok( chewing_cand_CurrentPage( ctx ) == 1, "current page shall be 1" );
+	ok( chewing_cursor_Current( ctx ) == 1, "cursor shall be 1" );
+
+	type_keystoke_by_string( ctx, "<PU>" );
+	ok( chewing_cand_CurrentPage( ctx ) == 0, "current page shall be 0" );
+
+	type_keystoke_by_string( ctx, "<PU>" );
+	ok( chewing_cand_CurrentPage( ctx ) == total_page - 1,
+		"current page shall wrap to the last page" );
+
+	type_keystoke_by_string( ctx, "<PD>" );
+	ok( chewing_cand_CurrentPage( ctx ) == 0,
+		"current page shall wrap to the first page" );
+
+	destroy_context( ctx );
+}
+
+void test_PageUp_PageDown_empty_buffer()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "<PU>" );
+	ok( chewing_keystroke_CheckIgnore( ctx ) == 1,
+		"PageUp on empty buffer shall be ignored" );
+
+	type_keystoke_by_string( ctx, "<PD>" );
+	ok( chewing_keystroke_CheckIgnore( ctx ) == 1,
+		"PageDown on empty buffer shall be ignored" );
+
+	destroy_context( ctx );
+}
+
+void test_Numlock_commit_on_empty_buffer()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "<N1>" );
+	ok_commit_buffer( ctx, "1" );
+	ok_preedit_buffer( ctx, "" );
+
+	destroy_context( ctx );
+}
+
+void test_Numlock_in_preedit_buffer()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4<N1><N2>" );
+	ok_preedit_buffer( ctx, "測12" );
+
+	destroy_context( ctx );
+}
+
+void test_Numlock_select_candidate()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<H><D><N2>" );
+	ok_preedit_buffer( ctx, "側室" );
+	ok( chewing_cand_TotalChoice( ctx ) == 0, "candidate window shall be closed" );
+
+	destroy_context( ctx );
+}
+
+void test_Numlock_not_as_selection()
+{
+	ChewingContext *ctx = create_context();
+
+	chewing_set_numpadAsSelection( ctx, 0 );
+
+	type_keystoke_by_string( ctx, "hk4g4<H><D><N2>" );
+	ok_preedit_buffer( ctx, "2測試" );
+	ok( chewing_cand_TotalChoice( ctx ) == 0, "candidate window shall be closed" );
+
+	destroy_context( ctx );
+}
+
+void test_keystroke_absorb()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "h" );
+	ok( chewing_keystroke_CheckAbsorb( ctx ) == 1, "keystroke shall be absorbed" );
+	ok( chewing_keystroke_CheckIgnore( ctx ) == 0, "keystroke shall not be ignored" );
+	ok( chewing_commit_Check( ctx ) == 0, "keystroke shall not commit" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 0, "keystroke shall not ring the bell" );
+
+	destroy_context( ctx );
+}
+
+void test_keystroke_ignore_non_printable()
+{
+	ChewingContext *ctx = create_context();
+
+	chewing_handle_Default( ctx, '\t' );
+	ok( chewing_keystroke_CheckIgnore( ctx ) == 1, "keystroke shall be ignored" );
+	ok( chewing_keystroke_CheckAbsorb( ctx ) == 0, "keystroke shall not be absorbed" );
+
+	type_keystoke_by_string( ctx, "hk4" );
+	chewing_handle_Default( ctx, '\t' );
+	ok( chewing_keystroke_CheckIgnore( ctx ) == 1, "keystroke shall be ignored" );
+	ok_preedit_buffer( ctx, "測" );
+
+	destroy_context( ctx );
+}
+
+void test_keystroke_commit()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4<E>" );
+	ok( chewing_commit_Check( ctx ) == 1, "keystroke shall commit" );
+	ok( chewing_keystroke_CheckIgnore( ctx ) == 0, "keystroke shall not be ignored" );
+	ok_commit_buffer( ctx, "測" );
+
+	type_keystoke_by_string( ctx, "<E>" );
+	ok( chewing_commit_Check( ctx ) == 0, "keystroke shall not commit" );
+	ok( chewing_keystroke_CheckIgnore( ctx ) == 1, "keystroke shall be ignored" );
+
+	destroy_context( ctx );
+}
+
+void test_keystroke_bell()
+{
+	ChewingContext *ctx = create_context();
+
+	/* ㄅㄧㄚˊ has no word */
+	type_keystoke_by_string( ctx, "1u86" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "keystroke shall ring the bell" );
+	ok( chewing_keystroke_CheckAbsorb( ctx ) == 1, "keystroke shall be absorbed" );
+
+	type_keystoke_by_string( ctx, "hk4<D><E>" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "keystroke shall ring the bell" );
+	ok( chewing_commit_Check( ctx ) == 0, "keystroke shall not commit" );
+
+	destroy_context( ctx );
+}
+
+void test_Numlock_select_last_candidate_in_page()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<L><D><N9>" );
+	ok_preedit_buffer( ctx, "測示" );
+
+	destroy_context( ctx );
+}
+
+void test_bell_invalid_key_in_select()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4<D>a" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "keystroke shall ring the bell" );
+	ok( chewing_cand_TotalChoice( ctx ) > 0, "candidate window shall be open" );
+
+	destroy_context( ctx );
+}
+
+void test_bell_no_candidate_for_select_key()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<H><D>" );
+	ok( chewing_cand_TotalChoice( ctx ) == 5, "total candidate shall be 5" );
+
+	type_keystoke_by_string( ctx, "9" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "keystroke shall ring the bell" );
+	ok( chewing_cand_TotalChoice( ctx ) == 5, "candidate window shall be open" );
+
+	type_keystoke_by_string( ctx, "<N9>" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "keystroke shall ring the bell" );
+
+	type_keystoke_by_string( ctx, "2" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 0, "keystroke shall not ring the bell" );
+	ok_preedit_buffer( ctx, "側室" );
+
+	destroy_context( ctx );
+}
+
+void test_bell_no_candidate_at_cursor()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4<CB>2<CB><D>" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "keystroke shall ring the bell" );
+	ok( chewing_cand_TotalChoice( ctx ) == 0, "candidate window shall be closed" );
+	ok( chewing_cursor_Current( ctx ) == 2, "cursor shall be 2" );
+
+	type_keystoke_by_string( ctx, "<L><L><D>" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 0, "keystroke shall not ring the bell" );
+
+	destroy_context( ctx );
+}
+
+void test_Del_remove_user_phrase_in_select()
+{
+	ChewingContext *ctx = create_context();
+
+	/* selecting a candidate adds it to the user phrase database */
+	type_keystoke_by_string( ctx, "hk4g4<H><D>2" );
+	ok_preedit_buffer( ctx, "側室" );
+
+	type_keystoke_by_string( ctx, "<D><DC>" );
+	ok( chewing_cand_TotalChoice( ctx ) > 0, "still in select mode" );
+	ok_aux_buffer( ctx, "刪除：" );
+
+	type_keystoke_by_string( ctx, "2" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 0, "user phrase is removed" );
+	ok_aux_buffer( ctx, "刪除：側室" );
+	ok( chewing_cand_TotalChoice( ctx ) > 0, "still in select mode" );
+
+	/* removing it again fails */
+	type_keystoke_by_string( ctx, "<DC>2" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "not a user phrase" );
+
+	chewing_delete( ctx );
+
+	/* the removal is persistent */
+	ctx = chewing_new();
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+	type_keystoke_by_string( ctx, "hk4g4<H><D><DC>2" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "not a user phrase" );
+
+	destroy_context( ctx );
+}
+
+void test_Del_cancel_remove_user_phrase()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4<H><D>2" );
+
+	type_keystoke_by_string( ctx, "<D><DC>" );
+	ok_aux_buffer( ctx, "刪除：" );
+
+	/* a key other than selection keys cancels the removal */
+	type_keystoke_by_string( ctx, "<DC>" );
+	type_keystoke_by_string( ctx, "j" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 0, "removal is cancelled" );
+	ok( chewing_cand_TotalChoice( ctx ) > 0, "still in select mode" );
+
+	type_keystoke_by_string( ctx, "2" );
+	ok_preedit_buffer( ctx, "側室" );
+	ok( chewing_cand_TotalChoice( ctx ) == 0, "candidate is selected" );
+
+	destroy_context( ctx );
+}
+
+void test_auto_learn_suppression()
+{
+	ChewingContext *ctx = create_context();
+
+	chewing_set_autoLearnSuppression( ctx, "測試", 1 );
+
+	/* suppressed phrase is not learned */
+	type_keystoke_by_string( ctx, "hk4g4<E>" );
+	ok_commit_buffer( ctx, "測試" );
+	type_keystoke_by_string( ctx, "hk4g4<H><D><DC>1" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "not a user phrase" );
+	type_keystoke_by_string( ctx, "<EE><E>" );
+
+	chewing_set_autoLearnSuppression( ctx, "測試", 0 );
+
+	type_keystoke_by_string( ctx, "hk4g4<E>" );
+	ok_commit_buffer( ctx, "測試" );
+	type_keystoke_by_string( ctx, "hk4g4<H><D><DC>1" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 0, "user phrase is learned" );
+
+	destroy_context( ctx );
+}
+
+void test_auto_learn_disabled()
+{
+	ChewingContext *ctx = create_context();
+
+	chewing_set_autoLearn( ctx, AUTOLEARN_DISABLED );
+
+	/* committed phrase is not learned */
+	type_keystoke_by_string( ctx, "hk4g4<E>" );
+	ok_commit_buffer( ctx, "測試" );
+	type_keystoke_by_string( ctx, "hk4g4<H><D><DC>1" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 1, "not a user phrase" );
+	type_keystoke_by_string( ctx, "<EE><E>" );
+
+	chewing_set_autoLearn( ctx, AUTOLEARN_ENABLED );
+
+	type_keystoke_by_string( ctx, "hk4g4<E>" );
+	ok_commit_buffer( ctx, "測試" );
+	type_keystoke_by_string( ctx, "hk4g4<H><D><DC>1" );
+	ok( chewing_keystroke_CheckBell( ctx ) == 0, "user phrase is learned" );
+
+	destroy_context( ctx );
+}
+
+void test_commit_preedit_buf()
+{
+	ChewingContext *ctx = create_context();
+
+	ok( chewing_commit_preedit_buf( ctx ) == -1,
+		"empty buffer shall not be committed" );
+
+	type_keystoke_by_string( ctx, "hk4g4hk" );
+	ok( chewing_commit_preedit_buf( ctx ) == 0,
+		"chewing_commit_preedit_buf shall return 0" );
+	ok_commit_buffer( ctx, "測試" );
+	ok_preedit_buffer( ctx, "" );
+	ok_zuin_buffer( ctx, "ㄘㄜ" );
+
+	type_keystoke_by_string( ctx, "4<D>" );
+	ok( chewing_commit_preedit_buf( ctx ) == -1,
+		"buffer shall not be committed when candidates are shown" );
+	ok_preedit_buffer( ctx, "測" );
+
+	destroy_context( ctx );
+}
+
+void test_clean_preedit_buf()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4hk" );
+	ok( chewing_clean_preedit_buf( ctx ) == 0,
+		"chewing_clean_preedit_buf shall return 0" );
+	ok_preedit_buffer( ctx, "" );
+	ok_zuin_buffer( ctx, "ㄘㄜ" );
+	ok( chewing_commit_Check( ctx ) == 0, "nothing shall be committed" );
+
+	type_keystoke_by_string( ctx, "4<D>" );
+	ok( chewing_clean_preedit_buf( ctx ) == -1,
+		"buffer shall not be cleaned when candidates are shown" );
+	ok_preedit_buffer( ctx, "測" );
+
+	destroy_context( ctx );
+}
+
+void test_clean_bopomofo_buf()
+{
+	ChewingContext *ctx = create_context();
+
+	type_keystoke_by_string( ctx, "hk4g4hk" );
+	ok( chewing_clean_bopomofo_buf( ctx ) == 0,
+		"chewing_clean_bopomofo_buf shall return 0" );
+	ok_preedit_buffer( ctx, "測試" );
+	ok_zuin_buffer( ctx, "" );
+
+	destroy_context( ctx );
+}
+
+static int logger_debug_count;
+
+static void logger( void *data, int level, const char *fmt UNUSED, ... )
+{
+	if ( level == CHEWING_LOG_DEBUG )
+		++*(int *) data;
+}
+
+void test_set_logger()
+{
+	ChewingContext *ctx = create_context();
+
+	logger_debug_count = 0;
+	chewing_set_logger( ctx, logger, &logger_debug_count );
+	type_keystoke_by_string( ctx, "hk4g4" );
+	ok_preedit_buffer( ctx, "測試" );
+	ok( logger_debug_count > 0, "logger shall receive debug message" );
+
+	logger_debug_count = 0;
+	chewing_set_logger( ctx, NULL, NULL );
+	type_keystoke_by_string( ctx, "<E>" );
+	ok_commit_buffer( ctx, "測試" );
+	ok( logger_debug_count == 0, "logger shall be removed" );
+
+	destroy_context( ctx );
+}
+
+int main()
+{
+	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
+	putenv( "CHEWING_USER_PATH=" TEST_HASH_DIR );
+
+	test_Backspace_remove_bopomofo();
+	test_Backspace_remove_bopomofo_before_char();
+	test_Backspace_remove_char_before_cursor();
+	test_Backspace_remove_symbol();
+	test_Backspace_remove_selected_interval();
+	test_Backspace_in_select();
+	test_Backspace_empty_buffer();
+
+	test_Del_remove_char_after_cursor();
+	test_Del_at_end_of_buffer();
+	test_Del_when_bopomofo_entering();
+	test_Del_keep_shifted_selected_interval();
+	test_Del_keep_shifted_breakpoint();
+	test_Del_keep_shifted_symbol();
+
+	test_Home_End();
+	test_PageUp_PageDown_not_in_select();
+	test_PageUp_PageDown_in_select();
+	test_PageUp_PageDown_empty_buffer();
+
+	test_Numlock_commit_on_empty_buffer();
+	test_Numlock_in_preedit_buffer();
+	test_Numlock_select_candidate();
+	test_Numlock_not_as_selection();
+
+	test_keystroke_absorb();
+	test_keystroke_ignore_non_printable();
+	test_keystroke_commit();
+	test_keystroke_bell();
+
+	test_Numlock_select_last_candidate_in_page();
+	test_bell_invalid_key_in_select();
+	test_bell_no_candidate_for_select_key();
+	test_bell_no_candidate_at_cursor();
+
+	test_Del_remove_user_phrase_in_select();
+	test_Del_cancel_remove_user_phrase();
+
+	test_auto_learn_suppression();
+	test_auto_learn_disabled();
+
+	test_set_logger();
+
+	test_commit_preedit_buf();
+	test_clean_preedit_buf();
+	test_clean_bopomofo_buf();
+
+	return exit_status();
+}
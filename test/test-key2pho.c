@@ -29,7 +29,7 @@ int main (int argc, char *argv[])
 	u8phone = "ㄒㄧㄚˋ";
 	ok (UintFromPhone(u8phone) == 7308, "UintFromPhone");
 
-	char rt[10];
+	char rt[16];
 
 	PhoneFromKey( rt, "dj", 0, 1 );
 	ok (!strcmp(rt, "ㄎㄨ"), "dj");
@@ -46,5 +46,32 @@ int main (int argc, char *argv[])
 	PhoneFromKey( rt, "dj7", 0, 1 );
 	ok (!strcmp(rt, "ㄎㄨ˙"), "dj7");
 
+	ok (PhoneFromUint(rt, sizeof(rt), 1104) == 2, "PhoneFromUint 1104");
+	ok (!strcmp(rt, "ㄆㄣ"), "ㄆㄣ");
+
+	ok (PhoneFromUint(rt, sizeof(rt), 7308) == 4, "PhoneFromUint 7308");
+	ok (!strcmp(rt, "ㄒㄧㄚˋ"), "ㄒㄧㄚˋ");
+
+	/* every valid phone shall round trip */
+	int initial, medial, final, tone, fail = 0;
+	uint16_t phone;
+	for (initial = 0; initial <= 21; initial++)
+	for (medial = 0; medial <= 3; medial++)
+	for (final = 0; final <= 13; final++)
+	for (tone = 0; tone <= 4; tone++) {
+		phone = (initial << 9) | (medial << 7) | (final << 3) | tone;
+		if (phone == 0)
+			continue;
+		if (!PhoneFromUint(rt, sizeof(rt), phone) || UintFromPhone(rt) != phone)
+			fail++;
+	}
+	ok (fail == 0, "PhoneFromUint and UintFromPhone round trip");
+
+	ok (PhoneFromUint(rt, sizeof(rt), 22 << 9) == 0, "invalid initial");
+	ok (PhoneFromUint(rt, sizeof(rt), 14 << 3) == 0, "invalid final");
+	ok (PhoneFromUint(rt, sizeof(rt), 5) == 0, "invalid tone");
+	ok (PhoneFromUint(rt, sizeof(rt), 7308 | 1 << 14) == 0, "invalid bits");
+	ok (PhoneFromUint(rt, 4, 7308) == 0, "buffer too small");
+
 	return exit_status();
 }
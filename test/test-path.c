@@ -15,8 +15,10 @@
 #include <stdlib.h>
 #include <string.h>
 
+#include "chewing.h"
 #include "test.h"
 #include "global-private.h"
+#include "hash-private.h"
 #include "plat_path.h"
 #include "plat_types.h"
 
@@ -80,10 +82,86 @@ void test_plat_path_cannot_find()
 	ok( ret != 0, "find_path_by_files shall not return 0" );
 }
 
+static int logger_error_count;
+
+static void logger( void *data, int level, const char *fmt UNUSED, ... )
+{
+	ok( data == &logger_error_count, "loggerdata shall be passed to logger" );
+	if ( level == CHEWING_LOG_ERROR )
+		++*(int *) data;
+}
+
+void test_new2_syspath()
+{
+	ChewingContext *ctx;
+
+	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX "_no_such_path" );
+
+	ctx = chewing_new();
+	ok( ctx == NULL, "chewing_new shall return NULL with wrong CHEWING_PATH" );
+
+	ctx = chewing_new2( CHEWING_DATA_PREFIX, TEST_HASH_DIR, NULL, NULL );
+	ok( ctx != NULL, "chewing_new2 shall use given syspath" );
+	chewing_delete( ctx );
+
+	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
+}
+
+void test_new2_syspath_error()
+{
+	ChewingContext *ctx;
+
+	logger_error_count = 0;
+	ctx = chewing_new2( CHEWING_DATA_PREFIX "_no_such_path", TEST_HASH_DIR,
+		logger, &logger_error_count );
+	ok( ctx == NULL, "chewing_new2 shall return NULL with wrong syspath" );
+	ok( logger_error_count > 0, "logger shall receive error message" );
+}
+
+void test_new2_userpath_error()
+{
+	ChewingContext *ctx;
+	char userpath[ 256 ];
+
+	logger_error_count = 0;
+	ctx = chewing_new2( CHEWING_DATA_PREFIX, TEST_HASH_DIR "_no_such_path",
+		logger, &logger_error_count );
+	ok( ctx == NULL, "chewing_new2 shall return NULL with wrong userpath" );
+	ok( logger_error_count > 0, "logger shall receive error message" );
+
+	/* the path fits in PATH_MAX but not in the user phrase file name */
+	memset( userpath, '/', sizeof( userpath ) - 1 );
+	userpath[ sizeof( userpath ) - 1 ] = '\0';
+	logger_error_count = 0;
+	ctx = chewing_new2( CHEWING_DATA_PREFIX, userpath,
+		logger, &logger_error_count );
+	ok( ctx == NULL, "chewing_new2 shall return NULL with too long userpath" );
+	ok( logger_error_count > 0, "logger shall receive error message" );
+}
+
+void test_path_Get()
+{
+	ChewingContext *ctx;
+
+	ctx = chewing_new2( CHEWING_DATA_PREFIX, TEST_HASH_DIR, NULL, NULL );
+	ok( ctx != NULL, "chewing_new2 shall not return NULL" );
+	ok( strcmp( chewing_dict_path_Get( ctx ), CHEWING_DATA_PREFIX ) == 0,
+		"chewing_dict_path_Get shall return " CHEWING_DATA_PREFIX );
+	ok( strcmp( chewing_userphrase_path_Get( ctx ),
+		TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE ) == 0,
+		"chewing_userphrase_path_Get shall return "
+		TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+	chewing_delete( ctx );
+}
+
 int main()
 {
 	test_plat_get_search_path();
 	test_plat_path_found();
 	test_plat_path_cannot_find();
+	test_new2_syspath();
+	test_new2_syspath_error();
+	test_new2_userpath_error();
+	test_path_Get();
 	return exit_status();
 }
@@ -162,6 +162,83 @@ void test_in_fullshape_mode()
 	chewing_Terminate();
 }
 
+void test_auto_pair_symbol()
+{
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+	chewing_set_autoPairSymbol( ctx, 1 );
+
+	type_keystoke_by_string( ctx, "[" );
+	ok_preedit_buffer( ctx, "「」" );
+	ok( chewing_cursor_Current( ctx ) == 1,
+		"cursor shall be between the pair" );
+
+	type_keystoke_by_string( ctx, "hk4g4" ); // 測試
+	ok_preedit_buffer( ctx, "「測試」" );
+	type_keystoke_by_string( ctx, "<E>" );
+	ok_commit_buffer( ctx, "「測試」" );
+
+	type_keystoke_by_string( ctx, "{<E>" );
+	ok_commit_buffer( ctx, "『』" );
+	type_keystoke_by_string( ctx, "(<E>" );
+	ok_commit_buffer( ctx, "（）" );
+
+	type_keystoke_by_string( ctx, "]<E>" );
+	ok_commit_buffer( ctx, "」" );
+
+	chewing_set_autoPairSymbol( ctx, 0 );
+	type_keystoke_by_string( ctx, "[<E>" );
+	ok_commit_buffer( ctx, "「" );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_symbol_key_layout()
+{
+	// Punctuation keys are bopomofo in some layouts, and symbols in the
+	// others.
+	static const struct {
+		const char *kbtype;
+		int shape;
+		const char *preedit;
+		const char *bopomofo;
+	} LAYOUT_DATA[] = {
+		{ "KB_DEFAULT", HALFSHAPE_MODE, "", "ㄦ" },
+		{ "KB_DEFAULT", FULLSHAPE_MODE, "", "ㄦ" },
+		{ "KB_HSU", HALFSHAPE_MODE, "，。/；-", "" },
+		{ "KB_HSU", FULLSHAPE_MODE, "，。／；－", "" },
+		{ "KB_ET26", HALFSHAPE_MODE, "，。/；-", "" },
+		{ "KB_ET26", FULLSHAPE_MODE, "，。／；－", "" },
+		{ "KB_HANYU_PINYIN", HALFSHAPE_MODE, "，。/；-", "" },
+		{ "KB_HANYU_PINYIN", FULLSHAPE_MODE, "，。／；－", "" },
+	};
+
+	chewing_Init( NULL, NULL );
+
+	ChewingContext *ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	for ( int i = 0; i < ARRAY_SIZE( LAYOUT_DATA ); ++i ) {
+		chewing_set_KBType( ctx, chewing_KBStr2Num( LAYOUT_DATA[i].kbtype ) );
+		chewing_set_ShapeMode( ctx, LAYOUT_DATA[i].shape );
+		type_keystoke_by_string( ctx, ",./;-" );
+		ok_preedit_buffer( ctx, LAYOUT_DATA[i].preedit );
+		ok( strcmp( chewing_bopomofo_String_static( ctx ), LAYOUT_DATA[i].bopomofo ) == 0,
+			"bopomofo shall be `%s'", LAYOUT_DATA[i].bopomofo );
+		chewing_Reset( ctx );
+	}
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
 int main()
 {
 	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
@@ -170,6 +247,8 @@ int main()
 	test_in_chinese_mode();
 	test_in_easy_symbol_mode();
 	test_in_fullshape_mode();
+	test_auto_pair_symbol();
+	test_symbol_key_layout();
 
 	return exit_status();
 }
@@ -0,0 +1,206 @@
+/**
+ * test-stress.c
+ *
+ * Copyright (c) 2012
+ *	libchewing Core Team. See ChangeLog for details.
+ *
+ * See the file "COPYING" for information on usage and redistribution
+ * of this file.
+ */
+
+#ifdef HAVE_CONFIG_H
+#include <config.h>
+#endif
+
+#include <stdlib.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "chewing.h"
+#include "chewing-utf8-util.h"
+#include "plat_types.h"
+#include "hash-private.h"
+#include "test.h"
+
+#define DEFAULT_SEED 1234
+#define DEFAULT_KEYSTROKE_NUM 20000
+
+static const char *KEYSTROKES[] = {
+	"1", "q", "a", "z", "2", "w", "s", "x", "e", "d", "c", "r", "f", "v",
+	"5", "t", "g", "b", "y", "h", "n", "u", "j", "m", "8", "i", "k", ",",
+	"9", "o", "l", ".", "0", "p", ";", "/", "-", "7", "6", "3", "4", " ",
+	"`", "A", "Z", "[", "]", "=", "?", "!",
+	"<L>", "<R>", "<U>", "<D>", "<E>", "<EE>", "<B>", "<DC>", "<H>", "<EN>",
+	"<T>", "<DT>", "<SL>", "<SR>", "<SS>", "<CB>", "<PU>", "<PD>",
+	"<C0>", "<C1>", "<C2>", "<C3>", "<C4>", "<C5>", "<C6>", "<C7>", "<C8>",
+	"<C9>", "<N0>", "<N1>", "<N5>", "<N9>",
+};
+
+static int is_valid_utf8( const char *str )
+{
+	int len, i;
+
+	while ( *str ) {
+		len = ueBytesFromChar( (unsigned char) *str );
+		if ( len <= 0 )
+			return 0;
+		for ( i = 1; i < len; ++i ) {
+			if ( ( str[ i ] & 0xC0 ) != 0x80 )
+				return 0;
+		}
+		str += len;
+	}
+	return 1;
+}
+
+static int is_valid_string( char *str )
+{
+	int ret = str && is_valid_utf8( str );
+	chewing_free( str );
+	return ret;
+}
+
+/*
+ * Return the description of the first broken invariant, or NULL if the
+ * context is consistent.
+ */
+static const char *check_invariant( ChewingContext *ctx )
+{
+	IntervalType it;
+	char *buf;
+	int len, buf_len, cursor, prev_to, zuin_count;
+
+	buf_len = chewing_buffer_Len( ctx );
+	cursor = chewing_cursor_Current( ctx );
+	if ( buf_len < 0 || cursor < 0 || cursor > buf_len )
+		return "cursor shall be within the pre-edit buffer";
+
+	buf = chewing_buffer_String( ctx );
+	if ( ! buf || ! is_valid_utf8( buf ) ) {
+		chewing_free( buf );
+		return "pre-edit buffer shall be valid UTF-8";
+	}
+	len = ueStrLen( buf );
+	chewing_free( buf );
+	if ( len != buf_len )
+		return "pre-edit buffer length shall match its content";
+
+	prev_to = 0;
+	chewing_interval_Enumerate( ctx );
+	while ( chewing_interval_hasNext( ctx ) ) {
+		chewing_interval_Get( ctx, &it );
+		if ( it.from < prev_to || it.from >= it.to || it.to > buf_len )
+			return "intervals shall be ordered and not overlap";
+		prev_to = it.to;
+	}
+
+	if ( chewing_commit_Check( ctx ) &&
+	     ! is_valid_string( chewing_commit_String( ctx ) ) )
+		return "commit buffer shall be valid UTF-8";
+
+	if ( ! is_valid_string( chewing_zuin_String( ctx, &zuin_count ) ) )
+		return "bopomofo buffer shall be valid UTF-8";
+
+	if ( chewing_aux_Check( ctx ) &&
+	     ! is_valid_string( chewing_aux_String( ctx ) ) )
+		return "aux buffer shall be valid UTF-8";
+
+	chewing_cand_Enumerate( ctx );
+	while ( chewing_cand_hasNext( ctx ) ) {
+		if ( ! is_valid_string( chewing_cand_String( ctx ) ) )
+			return "candidates shall be valid UTF-8";
+	}
+
+	return NULL;
+}
+
+/*
+ * Feed every byte of the input as a keystroke, and check the invariants
+ * after each keystroke.
+ */
+static void test_stress( const unsigned char *input, size_t input_len )
+{
+	ChewingContext *ctx;
+	const char *broken = NULL;
+	size_t i;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ctx = chewing_new();
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+	chewing_set_addPhraseDirection( ctx, 1 );
+	chewing_set_spaceAsSelection( ctx, 1 );
+
+	for ( i = 0; i < input_len && ! broken; ++i ) {
+		type_keystoke_by_string( ctx,
+			KEYSTROKES[ input[ i ] % ARRAY_SIZE( KEYSTROKES ) ] );
+		broken = check_invariant( ctx );
+	}
+
+	ok( broken == NULL, "%s (keystroke #%lu)",
+		broken ? broken : "invariants hold", (unsigned long) i );
+
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+static unsigned char *read_input( const char *filename, size_t *len )
+{
+	FILE *fp;
+	unsigned char *input = NULL;
+	unsigned char *tmp;
+	size_t size = 0, n;
+
+	fp = fopen( filename, "rb" );
+	if ( ! fp )
+		return NULL;
+	do {
+		tmp = realloc( input, size + BUFSIZ );
+		if ( ! tmp ) {
+			free( input );
+			fclose( fp );
+			return NULL;
+		}
+		input = tmp;
+		n = fread( input + size, 1, BUFSIZ, fp );
+		size += n;
+	} while ( n == BUFSIZ );
+	fclose( fp );
+
+	*len = size;
+	return input;
+}
+
+int main( int argc, char *argv[] )
+{
+	unsigned char *input;
+	size_t input_len, i;
+
+	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
+	putenv( "CHEWING_USER_PATH=" TEST_HASH_DIR );
+
+	if ( argc > 1 ) {
+		/* Replay the input file, e.g. a case found by a fuzzer. */
+		input = read_input( argv[ 1 ], &input_len );
+		if ( ! input ) {
+			fprintf( stderr, "Cannot read %s\n", argv[ 1 ] );
+			return 1;
+		}
+	}
+	else {
+		input_len = DEFAULT_KEYSTROKE_NUM;
+		input = malloc( input_len );
+		if ( ! input )
+			return 1;
+		srand( DEFAULT_SEED );
+		for ( i = 0; i < input_len; ++i )
+			input[ i ] = rand();
+	}
+
+	test_stress( input, input_len );
+	free( input );
+
+	return exit_status();
+}
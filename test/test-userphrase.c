@@ -0,0 +1,364 @@
+/**
+ * test-userphrase.c
+ *
+ * Copyright (c) 2012
+ *	libchewing Core Team. See ChangeLog for details.
+ *
+ * See the file "COPYING" for information on usage and redistribution
+ * of this file.
+ */
+
+#ifdef HAVE_CONFIG_H
+#include <config.h>
+#endif
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "chewing.h"
+#include "chewing-private.h"
+#include "plat_types.h"
+#include "hash-private.h"
+#include "test.h"
+
+static ChewingContext *create_context()
+{
+	ChewingContext *ctx;
+
+	remove( TEST_HASH_DIR PLAT_SEPARATOR HASH_FILE );
+
+	chewing_Init( NULL, NULL );
+
+	ctx = chewing_new();
+	ok( ctx, "chewing_new shall not return NULL" );
+	chewing_set_maxChiSymbolLen( ctx, 16 );
+
+	return ctx;
+}
+
+static void destroy_context( ChewingContext *ctx )
+{
+	chewing_delete( ctx );
+	chewing_Terminate();
+}
+
+void test_userphrase_add_remove()
+{
+	ChewingContext *ctx = create_context();
+
+	ok( chewing_userphrase_lookup( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 0,
+		"測試 shall not be a user phrase" );
+
+	ok( chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 1,
+		"測試 shall be added" );
+	ok( chewing_userphrase_lookup( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 1,
+		"測試 shall be a user phrase" );
+	ok( chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 0,
+		"existing phrase shall not be added" );
+
+	ok( chewing_userphrase_remove( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 1,
+		"測試 shall be removed" );
+	ok( chewing_userphrase_lookup( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 0,
+		"測試 shall not be a user phrase" );
+	ok( chewing_userphrase_remove( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 0,
+		"removed phrase shall not be removed again" );
+
+	destroy_context( ctx );
+}
+
+void test_userphrase_invalid()
+{
+	ChewingContext *ctx = create_context();
+
+	ok( chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ" ) == 0,
+		"bopomofo shall match the length of phrase" );
+	ok( chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ ㄕˋ ㄕˋ" ) == 0,
+		"bopomofo shall match the length of phrase" );
+	ok( chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ ㄕx" ) == 0,
+		"bopomofo shall not contain unknown symbol" );
+	ok( chewing_userphrase_add( ctx, "測試", "ㄜㄘˋ ㄕˋ" ) == 0,
+		"bopomofo shall be in order" );
+	ok( chewing_userphrase_add( ctx, "test", "ㄘㄜˋ ㄕˋ ㄘㄜˋ ㄕˋ" ) == 0,
+		"phrase shall not contain ASCII" );
+	ok( chewing_userphrase_add( ctx, "", "" ) == 0,
+		"empty phrase shall be rejected" );
+	ok( chewing_userphrase_add( ctx, NULL, "ㄘㄜˋ" ) == 0,
+		"NULL phrase shall be rejected" );
+	ok( chewing_userphrase_add( ctx, "測", NULL ) == 0,
+		"NULL bopomofo shall be rejected" );
+
+	destroy_context( ctx );
+}
+
+void test_userphrase_enumerate()
+{
+	ChewingContext *ctx = create_context();
+	char phrase[ 64 ];
+	char bopomofo[ 64 ];
+	unsigned int phrase_len, bopomofo_len;
+	int count = 0, found = 0;
+
+	chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ ㄕˋ" );
+	chewing_userphrase_add( ctx, "新酷音", "ㄒㄧㄣ ㄎㄨˋ ㄧㄣ" );
+
+	ok( chewing_userphrase_enumerate( ctx ) == 0,
+		"chewing_userphrase_enumerate shall return 0" );
+	while ( chewing_userphrase_has_next( ctx, &phrase_len, &bopomofo_len ) ) {
+		ok( chewing_userphrase_get( ctx, phrase, 1, bopomofo, 1 ) == -1,
+			"chewing_userphrase_get shall fail with small buffer" );
+		ok( phrase_len <= sizeof( phrase ) && bopomofo_len <= sizeof( bopomofo ),
+			"buffer shall be large enough" );
+		ok( chewing_userphrase_get( ctx, phrase, phrase_len,
+			bopomofo, bopomofo_len ) == 0,
+			"chewing_userphrase_get shall return 0" );
+		ok( strlen( phrase ) + 1 == phrase_len, "phrase_len shall match" );
+		ok( strlen( bopomofo ) + 1 == bopomofo_len, "bopomofo_len shall match" );
+
+		if ( ! strcmp( phrase, "測試" ) )
+			found += ! strcmp( bopomofo, "ㄘㄜˋ ㄕˋ" );
+		else if ( ! strcmp( phrase, "新酷音" ) )
+			found += ! strcmp( bopomofo, "ㄒㄧㄣ ㄎㄨˋ ㄧㄣ" );
+		++count;
+	}
+	ok( count == 2, "there shall be 2 user phrases" );
+	ok( found == 2, "all user phrases shall be enumerated" );
+
+	/* remove phrases during enumeration */
+	chewing_userphrase_enumerate( ctx );
+	while ( chewing_userphrase_has_next( ctx, &phrase_len, &bopomofo_len ) ) {
+		chewing_userphrase_get( ctx, phrase, sizeof( phrase ),
+			bopomofo, sizeof( bopomofo ) );
+		chewing_userphrase_remove( ctx, phrase, bopomofo );
+	}
+	chewing_userphrase_enumerate( ctx );
+	ok( chewing_userphrase_has_next( ctx, NULL, NULL ) == 0,
+		"all user phrases shall be removed" );
+
+	destroy_context( ctx );
+}
+
+void test_userphrase_persistence()
+{
+	ChewingContext *ctx = create_context();
+
+	chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ ㄕˋ" );
+	chewing_delete( ctx );
+
+	ctx = chewing_new();
+	ok( chewing_userphrase_lookup( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 1,
+		"added phrase shall be saved" );
+	chewing_userphrase_remove( ctx, "測試", "ㄘㄜˋ ㄕˋ" );
+	chewing_delete( ctx );
+
+	ctx = chewing_new();
+	ok( chewing_userphrase_lookup( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 0,
+		"removed phrase shall be saved" );
+
+	destroy_context( ctx );
+}
+
+void test_userphrase_recent_first()
+{
+	ChewingContext *ctx = create_context();
+
+	chewing_userphrase_add( ctx, "冊是", "ㄘㄜˋ ㄕˋ" );
+
+	type_keystoke_by_string( ctx, "hk4g4<H><D>" );
+	chewing_cand_Enumerate( ctx );
+	ok( strcmp( chewing_cand_String_static( ctx ), "冊是" ) != 0,
+		"user phrase shall not be the first candidate by default" );
+	type_keystoke_by_string( ctx, "<EE>" );
+	chewing_clean_preedit_buf( ctx );
+
+	chewing_set_recentPhraseFirst( ctx, 1 );
+	type_keystoke_by_string( ctx, "hk4g4<H><D>" );
+	chewing_cand_Enumerate( ctx );
+	ok( strcmp( chewing_cand_String_static( ctx ), "冊是" ) == 0,
+		"recent user phrase shall be the first candidate" );
+	ok( strcmp( chewing_cand_String_static( ctx ), "冊是" ) != 0,
+		"recent user phrase shall be listed once" );
+
+	destroy_context( ctx );
+}
+
+static int count_userphrase( ChewingContext *ctx )
+{
+	char phrase[ 64 ];
+	char bopomofo[ 64 ];
+	int count = 0;
+
+	chewing_userphrase_enumerate( ctx );
+	while ( chewing_userphrase_has_next( ctx, NULL, NULL ) ) {
+		chewing_userphrase_get( ctx, phrase, sizeof( phrase ),
+			bopomofo, sizeof( bopomofo ) );
+		++count;
+	}
+	return count;
+}
+
+void test_userphrase_max()
+{
+	ChewingContext *ctx = create_context();
+
+	/* chewing_handle_Space() advances the lifetime between phrases */
+	chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ ㄕˋ" );
+	chewing_handle_Space( ctx );
+	chewing_userphrase_add( ctx, "新酷音", "ㄒㄧㄣ ㄎㄨˋ ㄧㄣ" );
+	chewing_handle_Space( ctx );
+	chewing_userphrase_add( ctx, "冊是", "ㄘㄜˋ ㄕˋ" );
+	chewing_handle_Space( ctx );
+
+	ok( chewing_get_maxUserPhrase( ctx ) == 0,
+		"maxUserPhrase shall be 0 by default" );
+	chewing_set_maxUserPhrase( ctx, -1 );
+	ok( chewing_get_maxUserPhrase( ctx ) == 0,
+		"negative maxUserPhrase shall be ignored" );
+
+	chewing_set_maxUserPhrase( ctx, 2 );
+	ok( chewing_get_maxUserPhrase( ctx ) == 2, "maxUserPhrase shall be 2" );
+	ok( count_userphrase( ctx ) == 2, "there shall be 2 user phrases" );
+	ok( chewing_userphrase_lookup( ctx, "測試", "ㄘㄜˋ ㄕˋ" ) == 0,
+		"least recently used phrase shall be removed" );
+
+	ok( chewing_userphrase_add( ctx, "酷音", "ㄎㄨˋ ㄧㄣ" ) == 1,
+		"酷音 shall be added" );
+	ok( count_userphrase( ctx ) == 2, "user phrases shall not exceed the limit" );
+	ok( chewing_userphrase_lookup( ctx, "新酷音", "ㄒㄧㄣ ㄎㄨˋ ㄧㄣ" ) == 0,
+		"least recently used phrase shall be removed" );
+	ok( chewing_userphrase_lookup( ctx, "冊是", "ㄘㄜˋ ㄕˋ" ) == 1,
+		"冊是 shall be kept" );
+	chewing_delete( ctx );
+
+	ctx = chewing_new();
+	ok( count_userphrase( ctx ) == 2, "removed phrases shall not be loaded" );
+
+	destroy_context( ctx );
+}
+
+void test_userphrase_max_many()
+{
+	static const char *PHRASE[] = {
+		"測一", "測二", "測三", "測四", "測五", "測六",
+		"測七", "測八", "測九", "測十", "測百",
+	};
+	ChewingContext *ctx = create_context();
+	int i;
+
+	chewing_set_maxUserPhrase( ctx, 10 );
+	for ( i = 0; i < 10; ++i ) {
+		chewing_userphrase_add( ctx, PHRASE[ i ], "ㄘㄜˋ ㄕˋ" );
+		chewing_handle_Space( ctx );
+	}
+	ok( count_userphrase( ctx ) == 10, "there shall be 10 user phrases" );
+
+	chewing_userphrase_add( ctx, PHRASE[ 10 ], "ㄘㄜˋ ㄕˋ" );
+	chewing_handle_Space( ctx );
+	ok( count_userphrase( ctx ) == 10, "user phrases shall not exceed the limit" );
+	ok( chewing_userphrase_lookup( ctx, PHRASE[ 0 ], "ㄘㄜˋ ㄕˋ" ) == 0,
+		"least recently used phrase shall be removed" );
+	ok( chewing_userphrase_lookup( ctx, PHRASE[ 1 ], "ㄘㄜˋ ㄕˋ" ) == 1,
+		"only one phrase shall be removed" );
+
+	chewing_set_maxUserPhrase( ctx, 4 );
+	ok( count_userphrase( ctx ) == 4, "there shall be 4 user phrases" );
+	for ( i = 1; i < 7; ++i ) {
+		ok( chewing_userphrase_lookup( ctx, PHRASE[ i ], "ㄘㄜˋ ㄕˋ" ) == 0,
+			"least recently used phrases shall be removed" );
+	}
+	for ( i = 7; i < 11; ++i ) {
+		ok( chewing_userphrase_lookup( ctx, PHRASE[ i ], "ㄘㄜˋ ㄕˋ" ) == 1,
+			"recently used phrases shall be kept" );
+	}
+
+	destroy_context( ctx );
+}
+
+void test_userphrase_shared_file()
+{
+	ChewingContext *ctx = create_context();
+	ChewingContext *ctx2, *ctx3;
+
+	chewing_userphrase_add( ctx, "測試", "ㄘㄜˋ ㄕˋ" );
+	ctx2 = chewing_new();
+
+	/* 冊是 reuses the record of 測試 */
+	chewing_userphrase_remove( ctx, "測試", "ㄘㄜˋ ㄕˋ" );
+	chewing_userphrase_add( ctx, "冊是", "ㄘㄜˋ ㄕˋ" );
+
+	/* ctx2 still has 測試 at the reused record */
+	HashModify( ctx2->data, HashNext( ctx2->data, NULL ) );
+
+	ctx3 = chewing_new();
+	ok( chewing_userphrase_lookup( ctx3, "冊是", "ㄘㄜˋ ㄕˋ" ) == 1,
+		"record reused by another context shall not be overwritten" );
+	ok( chewing_userphrase_lookup( ctx3, "測試", "ㄘㄜˋ ㄕˋ" ) == 1,
+		"stale phrase shall be saved in a new record" );
+
+	/* 新酷音 reuses the record of 測試 */
+	chewing_userphrase_remove( ctx2, "測試", "ㄘㄜˋ ㄕˋ" );
+	chewing_userphrase_add( ctx2, "新酷音", "ㄒㄧㄣ ㄎㄨˋ ㄧㄣ" );
+
+	/* ctx3 still has 測試 at the reused record */
+	chewing_userphrase_remove( ctx3, "測試", "ㄘㄜˋ ㄕˋ" );
+	chewing_delete( ctx3 );
+
+	ctx3 = chewing_new();
+	ok( chewing_userphrase_lookup( ctx3, "新酷音", "ㄒㄧㄣ ㄎㄨˋ ㄧㄣ" ) == 1,
+		"record reused by another context shall not be cleared" );
+	chewing_delete( ctx3 );
+
+	chewing_delete( ctx2 );
+	destroy_context( ctx );
+}
+
+void test_userphrase_max_freq()
+{
+	ChewingContext *ctx = create_context();
+	HASH_ITEM *pItem;
+	char expected[ MAX_PHONE_SEQ_LEN * MAX_UTF8_SIZE + 1 ] = "";
+	int i;
+
+	chewing_set_maxChiSymbolLen( ctx, MAX_CHI_SYMBOL_LEN );
+	chewing_userphrase_add( ctx, "冊是", "ㄘㄜˋ ㄕˋ" );
+	chewing_userphrase_add( ctx, "情冊", "ㄑㄧㄥˊ ㄘㄜˋ" );
+
+	/* the sum of frequencies does not fit in int */
+	for ( pItem = HashNext( ctx->data, NULL ); pItem; pItem = HashNext( ctx->data, pItem ) )
+		pItem->data.userfreq = 1500000000;
+
+	/* 冊是 and 情冊 shall win over 事情 (ㄕˋ ㄑㄧㄥˊ) */
+	type_keystoke_by_string( ctx, "hk4g4fu/6hk4" );
+	ok_preedit_buffer( ctx, "冊是情冊" );
+	chewing_clean_preedit_buf( ctx );
+
+	/* fill the buffer as much as possible without committing */
+	for ( i = 0; i < ( MAX_CHI_SYMBOL_LEN - ZUIN_SIZE - 3 ) / 2; ++i ) {
+		type_keystoke_by_string( ctx, "hk4g4" );
+		strcat( expected, "冊是" );
+	}
+	type_keystoke_by_string( ctx, "fu/6hk4" );
+	strcat( expected, "情冊" );
+	ok( chewing_buffer_Len( ctx ) == i * 2 + 2, "nothing shall be committed" );
+	ok_preedit_buffer( ctx, expected );
+
+	destroy_context( ctx );
+}
+
+int main()
+{
+	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
+	putenv( "CHEWING_USER_PATH=" TEST_HASH_DIR );
+
+	test_userphrase_add_remove();
+	test_userphrase_invalid();
+	test_userphrase_enumerate();
+	test_userphrase_persistence();
+	test_userphrase_recent_first();
+	test_userphrase_max();
+	test_userphrase_max_many();
+	test_userphrase_shared_file();
+	test_userphrase_max_freq();
+
+	return exit_status();
+}
@@ -14,29 +14,41 @@
 #include <stdio.h>
 #include <string.h>
 
+#include "chewing-utf8-util.h"
+
 static unsigned int test_run;
 static unsigned int test_ok;
 
+/* chewing_zuin_Check() returns 1 when there is no zuin in the buffer */
+static int zuin_check( ChewingContext *ctx )
+{
+	return !chewing_zuin_Check( ctx );
+}
+
 BufferType COMMIT_BUFFER = {
 	.check = chewing_commit_Check,
 	.get_string = chewing_commit_String,
+	.get_string_static = chewing_commit_String_static,
 };
 
 BufferType PREEDIT_BUFFER = {
 	.check = chewing_buffer_Check,
 	.get_length = chewing_buffer_Len,
 	.get_string = chewing_buffer_String,
+	.get_string_static = chewing_buffer_String_static,
 };
 
 BufferType ZUIN_BUFFER = {
-	.check = chewing_zuin_Check,
+	.check = zuin_check,
 	.get_string_alt = chewing_zuin_String,
+	.get_string_static = chewing_bopomofo_String_static,
 };
 
 BufferType AUX_BUFFER = {
 	.check = chewing_aux_Check,
 	.get_length = chewing_aux_Length,
 	.get_string = chewing_aux_String,
+	.get_string_static = chewing_aux_String_static,
 };
 
 int get_keystroke( get_char_func get_char, void * param )
@@ -77,6 +89,8 @@ int get_keystroke( get_char_func get_char, void * param )
 						return result = KEY_DOWN;
 					else {
 						get_char( param );
+						if ( ch == 'T' )
+							return result = KEY_DBLTAB;
 						return result = KEY_DELETE;
 					}
 					break;
@@ -105,12 +119,24 @@ int get_keystroke( get_char_func get_char, void * param )
 				case 'S':
 					if ( ( ch = get_char( param ) ) == 'L' )
 						result = KEY_SLEFT;
+					else if ( ch == 'S' )
+						result = KEY_SSPACE;
 					else
 						result = KEY_SRIGHT;
 					break;
 				case 'T':
 					result = KEY_TAB;
 					break;
+				case 'P':
+					if ( ( ch = get_char( param ) ) == 'U' )
+						result = KEY_PAGEUP;
+					else
+						result = KEY_PAGEDOWN;
+					break;
+				case 'N':
+					if ( ( ch = get_char( param ) ) != '>' )
+						result = ( KEY_NUMPAD_BASE + ch );
+					break;
 			}
 		}
 	}
@@ -121,6 +147,7 @@ static void type_keystoke( ChewingContext *ctx, get_char_func get_char, void *pa
 {
 	int ch;
 	int ctrl_shifted;
+	int numpad;
 
 	while ( ( ch = get_keystroke( get_char, param ) ) != END ) {
 		switch ( ch ) {
@@ -169,10 +196,25 @@ static void type_keystoke( ChewingContext *ctx, get_char_func get_char, void *pa
 			case KEY_CAPSLOCK:
 				chewing_handle_Capslock( ctx );
 				break;
+			case KEY_SSPACE:
+				chewing_handle_ShiftSpace( ctx );
+				break;
+			case KEY_DBLTAB:
+				chewing_handle_DblTab( ctx );
+				break;
+			case KEY_PAGEUP:
+				chewing_handle_PageUp( ctx );
+				break;
+			case KEY_PAGEDOWN:
+				chewing_handle_PageDown( ctx );
+				break;
 			default:
 				ctrl_shifted = ( ch - KEY_CTRL_BASE );
+				numpad = ( ch - KEY_NUMPAD_BASE );
 				if ( ( ctrl_shifted >= '0' ) && ( ctrl_shifted <= '9' ) ) {
 					chewing_handle_CtrlNum( ctx, ctrl_shifted );
+				} else if ( ( numpad >= '0' ) && ( numpad <= '9' ) ) {
+					chewing_handle_Numlock( ctx, numpad );
 				} else {
 					chewing_handle_Default( ctx, (char) ch );
 				}
@@ -251,7 +293,7 @@ void internal_ok_buffer( const char *file, int line, ChewingContext *ctx,
 
 	if ( buffer->get_length ) {
 		actual_ret = buffer->get_length( ctx );
-		expected_ret = expected_len;
+		expected_ret = ueStrLen( expected );
 		internal_ok( file, line, actual_ret == expected_ret,
 			"actual_ret == expected_ret",
 			"get length function returned `%d' shall be `%d'", actual_ret, expected_ret );
@@ -266,7 +308,7 @@ void internal_ok_buffer( const char *file, int line, ChewingContext *ctx,
 
 	if ( buffer->get_string_alt ) {
 		buf = buffer->get_string_alt( ctx, &actual_ret );
-		expected_ret = expected_len;
+		expected_ret = ueStrLen( expected );
 		internal_ok( file, line, actual_ret == expected_ret,
 			"actual_ret == expected_ret",
 			"string function returned parameter `%d' shall be `%d'", actual_ret, expected_ret );
@@ -274,6 +316,13 @@ void internal_ok_buffer( const char *file, int line, ChewingContext *ctx,
 			"string function returned `%s' shall be `%s'", buf, expected );
 		chewing_free( buf );
 	}
+
+	if ( buffer->get_string_static ) {
+		const char *const_buf = buffer->get_string_static( ctx );
+		internal_ok( file, line, !strcmp( const_buf, expected ),
+			"!strcmp( const_buf, expected )",
+			"static string function returned `%s' shall be `%s'", const_buf, expected );
+	}
 }
 
 void internal_ok_candidate( const char *file, int line,
@@ -281,6 +330,7 @@ void internal_ok_candidate( const char *file, int line,
 {
 	int i;
 	char *buf;
+	const char *const_buf;
 
 	assert( ctx );
 	assert( cand );
@@ -303,6 +353,16 @@ void internal_ok_candidate( const char *file, int line,
 		"candndate `%s' shall be `%s'", buf, "" );
 
 	chewing_free( buf );
+
+	chewing_cand_Enumerate( ctx );
+	for ( i = 0; i < cand_len; ++i ) {
+		const_buf = chewing_cand_String_static( ctx );
+		internal_ok( file, line, strcmp( const_buf, cand[i] ) == 0, __func__,
+			"static candidate `%s' shall be `%s'", const_buf, cand[i] );
+	}
+	const_buf = chewing_cand_String_static( ctx );
+	internal_ok( file, line, strcmp( const_buf, "" ) == 0, __func__,
+		"static candidate `%s' shall be `%s'", const_buf, "" );
 }
 
 int exit_status()
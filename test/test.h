@@ -14,6 +14,10 @@
 
 #include "chewing.h"
 
+#define KEY_SSPACE 892
+#define KEY_DBLTAB 893
+#define KEY_PAGEUP 894
+#define KEY_PAGEDOWN 895
 #define KEY_SLEFT 896
 #define KEY_SRIGHT 897
 #define KEY_LEFT 898
@@ -30,6 +34,7 @@
 #define KEY_TAB 998
 #define KEY_CAPSLOCK 999
 #define KEY_CTRL_BASE 1000
+#define KEY_NUMPAD_BASE 1100
 #define END 2000
 
 #define ARRAY_SIZE(array) ( sizeof(array) / sizeof(array[0] ) )
@@ -57,6 +62,7 @@ typedef struct {
 	int (*get_length)(ChewingContext *ctx);
 	char * (*get_string)(ChewingContext *ctx);
 	char * (*get_string_alt)(ChewingContext *ctx, int *len);
+	const char * (*get_string_static)(ChewingContext *ctx);
 } BufferType;
 
 extern BufferType COMMIT_BUFFER;
@@ -15,6 +15,10 @@
 #include <stdlib.h>
 #include <string.h>
 
+#define KEY_SSPACE 892
+#define KEY_DBLTAB 893
+#define KEY_PAGEUP 894
+#define KEY_PAGEDOWN 895
 #define KEY_SLEFT 896
 #define KEY_SRIGHT 897
 #define KEY_LEFT 898
@@ -31,6 +35,7 @@
 #define KEY_TAB 998
 #define KEY_CAPSLOCK 999
 #define KEY_CTRL_BASE 1000
+#define KEY_NUMPAD_BASE 1100
 #define END 2000
 
 #ifdef USED_IN_SIMULATION
@@ -107,6 +112,7 @@ int main( int argc, char *argv[] )
 	char *prefix = CHEWING_DATA_PREFIX;
 	int i;
 	int ctrl_shifted;
+	int numpad;
 
 	/* Initialize libchewing */
 	putenv( "CHEWING_PATH=" CHEWING_DATA_PREFIX );
@@ -174,12 +180,27 @@ int main( int argc, char *argv[] )
 			case KEY_CAPSLOCK:
 				chewing_handle_Capslock( ctx );
 				break;
+			case KEY_SSPACE:
+				chewing_handle_ShiftSpace( ctx );
+				break;
+			case KEY_DBLTAB:
+				chewing_handle_DblTab( ctx );
+				break;
+			case KEY_PAGEUP:
+				chewing_handle_PageUp( ctx );
+				break;
+			case KEY_PAGEDOWN:
+				chewing_handle_PageDown( ctx );
+				break;
 			case END:
 				goto end;
 			default:
 				ctrl_shifted = ( i - KEY_CTRL_BASE );
+				numpad = ( i - KEY_NUMPAD_BASE );
 				if ( ( ctrl_shifted >= '0' ) && ( ctrl_shifted <= '9' ) ) {
 					chewing_handle_CtrlNum( ctx, ctrl_shifted );
+				} else if ( ( numpad >= '0' ) && ( numpad <= '9' ) ) {
+					chewing_handle_Numlock( ctx, numpad );
 				} else {
 					chewing_handle_Default( ctx, (char) i );
 				}